@@ -1,6 +1,12 @@
 #![allow(non_snake_case, unused)]
 
-use std::{sync::{Arc, Mutex, Condvar}, collections::VecDeque, mem::swap};
+use self::sync::{Arc, Mutex, MutexGuard, Condvar};
+use std::{
+  collections::VecDeque, mem::swap, fmt, error::Error, ops::Deref, time::{Duration, Instant}
+};
+
+#[cfg(feature = "async")]
+use std::{pin::Pin, task::{Context, Poll, Waker}};
 
 /*
   NOTE :
@@ -11,18 +17,129 @@ use std::{sync::{Arc, Mutex, Condvar}, collections::VecDeque, mem::swap};
   Arc<Mutex<T>> - Shared ownership of a resource across threads.
 */
 
+// The synchronization primitives the channels are built upon. When compiled with '--cfg loom',
+// they are swapped out with loom's versions, which let the loom tests explore every possible
+// interleaving of the threads using a channel.
+mod sync {
+  #[cfg(not(loom))]
+  pub(crate) use std::{
+    sync::{Arc, Mutex, MutexGuard, Condvar, atomic::{AtomicUsize, AtomicBool, fence, Ordering}},
+    hint::spin_loop
+  };
+
+  #[cfg(loom)]
+  pub(crate) use loom::{
+    sync::{Arc, Mutex, MutexGuard, Condvar, atomic::{AtomicUsize, AtomicBool, fence, Ordering}},
+    hint::spin_loop,
+    cell::UnsafeCell
+  };
+
+  // std's UnsafeCell, behind the closure based API of loom's one (which needs to track every
+  // access to the cell).
+  #[cfg(not(loom))]
+  pub(crate) struct UnsafeCell<T>(std::cell::UnsafeCell<T>);
+
+  #[cfg(not(loom))]
+  impl<T> UnsafeCell<T> {
+    pub(crate) fn new(t: T) -> Self {
+      Self(std::cell::UnsafeCell::new(t))
+    }
+
+    pub(crate) fn with_mut<R>(&self, f: impl FnOnce(*mut T) -> R) -> R {
+      f(self.0.get( ))
+    }
+  }
+}
+
+// Shorthand for Channel::new.
+pub fn channel<T>( ) -> (Sender<T>, Receiver<T>) {
+  Channel::new( )
+}
+
+// Shorthand for Channel::bounded.
+pub fn bounded<T>(capacity: usize) -> (SyncSender<T>, Receiver<T>) {
+  Channel::bounded(capacity)
+}
+
+// use channels::prelude::*; brings in everything needed to work with the regular channel flavours.
+pub mod prelude {
+  pub use super::{
+    channel, bounded,
+    Sender, SyncSender, WeakSender, Receiver,
+    SendError, TrySendError, SendTimeoutError, RecvError, TryRecvError,
+    BackpressureEvent
+  };
+}
+
 pub struct Sender<T> {
   channel: Arc<Channel<T>>
 }
 
 impl<T> Sender<T> {
-  pub fn send(&mut self, t: T) {
+  // Fails (handing the value back) once the channel has been closed from either side or the
+  // receiver is gone.
+  pub fn send(&self, t: T) -> Result<( ), SendError<T>> {
     let mut queueRefWithMutexLock= self.channel.queue.lock( ).unwrap( );
-    queueRefWithMutexLock._queue.push_back(t);
+    if queueRefWithMutexLock.isDisconnectedForSenders( ) {
+      return Err(SendError(t))}
+
+    queueRefWithMutexLock.push(t);
+
+    self.channel.notifyReceiver(queueRefWithMutexLock);
+    Ok(( ))
+  }
+
+  // Sends every item while taking the mutex lock only once, and wakes the receiver only once. On a
+  // closed channel, nothing is sent and the untouched iterator is handed back.
+  pub fn send_all<I>(&self, items: I) -> Result<( ), SendError<I>>
+    where I: IntoIterator<Item = T>
+  {
+    let mut queueRefWithMutexLock= self.channel.queue.lock( ).unwrap( );
+    if queueRefWithMutexLock.isDisconnectedForSenders( ) {
+      return Err(SendError(items))}
+
+    queueRefWithMutexLock.extend(items);
+
+    self.channel.notifyReceiver(queueRefWithMutexLock);
+    Ok(( ))
+  }
+
+  // Signals "no more messages" without dropping the handle. Items which are already queued are
+  // still delivered to the receiver.
+  pub fn close(&self) {
+    self.channel.close( )}
+
+  // Like close, but once the already queued items are drained, the receiver gets told that
+  // something went wrong on the sending side (say, a multi-part message got left half-sent). Only
+  // the first reason is kept.
+  pub fn poison(&self, reason: &'static str) {
+    self.channel.poison(reason)}
+
+  // Poisons the channel, if the guard gets dropped while the thread is panicking. Keep it alive
+  // for as long as a panic would leave the receiver hanging.
+  pub fn panic_guard(&self) -> PanicGuard<'_, T> {
+    PanicGuard { sender: self }
+  }
+
+  pub fn stats(&self) -> ChannelStats {
+    self.channel.queue.lock( ).unwrap( ).stats( )}
+
+  // Number of items waiting in the shared queue. Items which the receiver has already moved into
+  // its cache aren't visible to the senders. So, this is a lower bound of Receiver::len.
+  pub fn queued_len(&self) -> usize {
+    self.channel.queue.lock( ).unwrap( )._queue.len( )}
+
+  // Whether sending would fail, because the receiver is gone or the channel is closed. A cheap way
+  // to check if anyone is still listening, before doing expensive work to build a message.
+  pub fn is_disconnected(&self) -> bool {
+    self.channel.queue.lock( ).unwrap( ).isDisconnectedForSenders( )}
+
+  // Whether both the handles belong to the same channel.
+  pub fn same_channel(&self, other: &Self) -> bool {
+    Arc::ptr_eq(&self.channel, &other.channel)}
 
-    drop(queueRefWithMutexLock); // Dropping the mutex lock first, so that when the receiver wakes
-                                 // up, it can immediately take the mutex lock.
-    self.channel.condVar.notify_one( );
+  pub fn downgrade(&self) -> WeakSender<T> {
+    WeakSender { channel: Arc::clone(&self.channel) }
   }
 }
 
@@ -44,12 +161,265 @@ impl<T> Drop for Sender<T> {
     let mut queueRefWithMutexLock= self.channel.queue.lock( ).unwrap( );
     queueRefWithMutexLock.senderCount -= 1;
 
-    let noSenders= (queueRefWithMutexLock.senderCount == 0);
+    if queueRefWithMutexLock.senderCount == 0 {
+      self.channel.notifyReceiver(queueRefWithMutexLock)}
+  }
+}
+
+// Created by Sender::panic_guard.
+#[must_use = "the channel gets poisoned only if the guard is alive while panicking"]
+pub struct PanicGuard<'a, T> {
+  sender: &'a Sender<T>
+}
+
+impl<T> Drop for PanicGuard<'_, T> {
+  fn drop(&mut self) {
+    if std::thread::panicking( ) {
+      self.sender.poison("a sender panicked")}
+  }
+}
+
+// The sending half of a bounded channel. Sending blocks while the queue is full.
+pub struct SyncSender<T> {
+  sender: Sender<T>,
+
+  // Item buffered by Sink::start_send, until it gets flushed into the channel.
+  #[cfg(feature = "async")]
+  pending: Option<T>
+}
+
+impl<T> SyncSender<T> {
+  pub fn send(&self, t: T) -> Result<( ), SendError<T>> {
+    self.sendBefore(t, None).map_err(|error| SendError(error.into_inner( )))
+  }
+
+  // Never blocks. Fails with Full if there's no space in the queue right now.
+  pub fn try_send(&self, t: T) -> Result<( ), TrySendError<T>> {
+    let mut queueRefWithMutexLock= self.sender.channel.queue.lock( ).unwrap( );
+
+    if queueRefWithMutexLock.isDisconnectedForSenders( ) {
+      return Err(TrySendError::Disconnected(t))}
+
+    if !queueRefWithMutexLock.maySend(None) {
+      return Err(TrySendError::Full(t))}
+
+    queueRefWithMutexLock.push(t);
+
+    self.sender.channel.notifyReceiver(queueRefWithMutexLock);
+    Ok(( ))
+  }
+
+  // Blocks for at most the given duration, waiting for some space in the queue.
+  pub fn send_timeout(&self, t: T, timeout: Duration) -> Result<( ), SendTimeoutError<T>> {
+    self.sendBefore(t, Some(Instant::now( ) + timeout))
+  }
+
+  // Blocks till there's space in the queue, or the deadline (if any) passes.
+  fn sendBefore(&self, t: T, deadline: Option<Instant>) -> Result<( ), SendTimeoutError<T>> {
+    let channel= &self.sender.channel;
+
+    // Set when the sender starts blocking, so that the backpressure hook (if any) can be told how
+    // long the sender waited, once it unblocks.
+    let mut blockedSince: Option<Instant>= None;
+
+    // Taken when the sender starts blocking, in case of a fair channel.
+    let mut ticket: Option<u64>= None;
+
+    let mut queueRefWithMutexLock= channel.queue.lock( ).unwrap( );
+    let result= loop {
+      if queueRefWithMutexLock.isDisconnectedForSenders( ) {
+        break Err(SendTimeoutError::Disconnected(t))}
+
+      if queueRefWithMutexLock.maySend(ticket) {
+        break Ok(t)}
+
+      if blockedSince.is_none( ) {
+        blockedSince= Some(Instant::now( ));
+        queueRefWithMutexLock.waitingSenders += 1;
+
+        if queueRefWithMutexLock.fair {
+          ticket= Some(queueRefWithMutexLock.takeTicket( ))}
+
+        // The hook is run without holding the mutex lock, so that it can't stall the receiver (or
+        // deadlock by using the channel). Since the queue might have changed meanwhile, we then
+        // start over.
+        if let Some(hook)= queueRefWithMutexLock.backpressureHook.clone( ) {
+          let queueLength= queueRefWithMutexLock._queue.len( );
+          drop(queueRefWithMutexLock);
+
+          hook(BackpressureEvent::Blocked { queueLength });
+
+          queueRefWithMutexLock= channel.queue.lock( ).unwrap( );
+          continue
+        }
+      }
+
+      // Wait till the receiver frees up some space in the queue (and, for a fair channel, till the
+      // senders which started waiting before us are done).
+      queueRefWithMutexLock= match deadline {
+        None => channel.senderCondVar.wait(queueRefWithMutexLock).unwrap( ),
+
+        // We might get woken up spuriously, or lose the race for the freed up space to some other
+        // sender. So, the remaining time is recomputed before waiting again.
+        Some(deadline) => {
+          let now= Instant::now( );
+          if now >= deadline {
+            break Err(SendTimeoutError::Timeout(t))}
+
+          channel.senderCondVar.wait_timeout(queueRefWithMutexLock, deadline - now).unwrap( ).0
+        }
+      };
+    };
+
+    if blockedSince.is_some( ) {
+      queueRefWithMutexLock.waitingSenders -= 1}
+
+    if let Some(ticket)= ticket {
+      queueRefWithMutexLock.tickets.retain(|&waitingTicket| waitingTicket != ticket)}
+
+    let queueLength= queueRefWithMutexLock._queue.len( );
+    let hook= blockedSince.and(queueRefWithMutexLock.backpressureHook.clone( ));
+
+    let result= match result {
+      Ok(t) => {
+        queueRefWithMutexLock.push(t);
+        channel.notifyReceiver(queueRefWithMutexLock);
+        Ok(( ))
+      },
+
+      Err(error) => {
+        drop(queueRefWithMutexLock);
+        Err(error)
+      }
+    };
+
+    // We were holding up the senders behind us in the line. The next one in line might be able to
+    // send right away (say, the receiver freed up multiple slots at once).
+    if ticket.is_some( ) {
+      channel.notifySenders(channel.queue.lock( ).unwrap( ))}
+
+    // The sender unblocked : either it got some space in the queue, or it gave up.
+    if let (Some(blockedSince), Some(hook))= (blockedSince, hook) {
+      hook(BackpressureEvent::Unblocked { queueLength, waited: blockedSince.elapsed( ) })}
+
+    result
+  }
+
+  // Installs (or replaces) the hook that gets told whenever a blocking send on this channel starts
+  // waiting for space in the queue, and when it stops waiting. The hook is shared by all the
+  // senders of the channel. Only send and send_timeout report to it, since try_send and poll_send
+  // never block the thread.
+  pub fn set_backpressure_hook(&self, hook: impl Fn(BackpressureEvent) + Send + Sync + 'static) {
+    self.sender.channel.queue.lock( ).unwrap( ).backpressureHook= Some(std::sync::Arc::new(hook));
+  }
+
+  // Number of senders currently blocked in send / send_timeout, waiting for space in the queue.
+  pub fn waiting_senders(&self) -> usize {
+    self.sender.channel.queue.lock( ).unwrap( ).waitingSenders
+  }
+
+  // The non-blocking counterpart of send, for async code. The item is taken out of the slot only
+  // once it's sent (or handed back inside the error). When the queue is full, the item stays in the
+  // slot and the task's waker is registered, so that the receiver wakes the task up after freeing
+  // some space.
+  #[cfg(feature = "async")]
+  pub fn poll_send(&self, cx: &mut Context<'_>, item: &mut Option<T>)
+    -> Poll<Result<( ), SendError<T>>>
+  {
+    self.sender.channel.pollSend(cx, item)
+  }
+
+  pub fn close(&self) {
+    self.sender.close( )}
+
+  pub fn poison(&self, reason: &'static str) {
+    self.sender.poison(reason)}
+
+  pub fn panic_guard(&self) -> PanicGuard<'_, T> {
+    self.sender.panic_guard( )}
+
+  pub fn stats(&self) -> ChannelStats {
+    self.sender.stats( )}
+
+  pub fn queued_len(&self) -> usize {
+    self.sender.queued_len( )}
+
+  pub fn is_disconnected(&self) -> bool {
+    self.sender.is_disconnected( )}
+
+  pub fn same_channel(&self, other: &Self) -> bool {
+    self.sender.same_channel(&other.sender)}
+}
+
+impl<T> Clone for SyncSender<T> {
+  fn clone(&self) -> Self {
+    Self {
+      sender: self.sender.clone( ),
+
+      #[cfg(feature = "async")]
+      pending: None
+    }
+  }
+}
+
+// The pending item is never pinned, so moving the sender around is always fine.
+impl<T> Unpin for SyncSender<T> { }
+
+#[cfg(feature = "async")]
+impl<T> futures_sink::Sink<T> for SyncSender<T> {
+  type Error = SendError<T>;
+
+  fn poll_ready(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<( ), Self::Error>> {
+    // The single slot needs to be free before accepting the next item.
+    self.poll_flush(cx)
+  }
+
+  fn start_send(self: Pin<&mut Self>, item: T) -> Result<( ), Self::Error> {
+    self.get_mut( ).pending= Some(item);
+    Ok(( ))
+  }
+
+  fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<( ), Self::Error>> {
+    let this= self.get_mut( );
+    this.sender.channel.pollSend(cx, &mut this.pending)
+  }
+
+  // Only flushes. Like with the blocking API, the channel gets disconnected once every sender is
+  // dropped.
+  fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<( ), Self::Error>> {
+    self.poll_flush(cx)
+  }
+}
+
+// A WeakSender doesn't count as a sender. So, the receiver sees the channel as disconnected once all
+// the (strong) Senders are dropped, even if WeakSenders are still around. It only keeps the shared
+// allocation alive, not the channel itself.
+pub struct WeakSender<T> {
+  channel: Arc<Channel<T>>
+}
+
+impl<T> WeakSender<T> {
+  // Fails if all the Senders are already gone, or the receiver is gone, or the channel is closed -
+  // a dead channel is never resurrected.
+  pub fn upgrade(&self) -> Option<Sender<T>> {
+    let mut queueRefWithMutexLock= self.channel.queue.lock( ).unwrap( );
+    let senderCount= queueRefWithMutexLock.senderCount;
+    if senderCount == 0 || queueRefWithMutexLock.isDisconnectedForSenders( ) {
+      return None}
+
+    queueRefWithMutexLock.senderCount += 1;
 
     drop(queueRefWithMutexLock);
 
-    if noSenders {
-      self.channel.condVar.notify_one( )}
+    Some(Sender { channel: Arc::clone(&self.channel) })
+  }
+}
+
+impl<T> Clone for WeakSender<T> {
+  fn clone(&self) -> Self {
+    Self {
+      channel: Arc::clone(&self.channel)
+    }
   }
 }
 
@@ -57,103 +427,2773 @@ pub struct Receiver<T> {
   channel: Arc<Channel<T>>,
 
   // Assuming that we only have 1 receiver, we do a little optimization 😉.
-  cache: VecDeque<T>
+  cache: VecDeque<T>,
+
+  // Maximum number of items moved from the shared queue into the cache, per mutex lock acquisition.
+  cacheBatch: usize,
+
+  // Once the cache drains down to these many items, the receiver goes back to the shared queue and
+  // tops the cache up, instead of waiting for it to run dry. 0 means it waits.
+  cacheRefillThreshold: usize,
+
+  // Number of items handed out from the cache, which are yet to be accounted for in the shared
+  // statistics. We do that the next time we take the mutex lock anyways.
+  cacheReceived: u64
 }
 
 impl<T> Receiver<T> {
-  pub fn receive(&mut self) -> Option<T> {
-    if let Some(t)= self.cache.pop_front( ) {
-      return Some(t)}
+  // Blocks till there's something to receive. Fails once nothing is left to be received and no more
+  // items can arrive, telling why.
+  pub fn recv(&mut self) -> Result<T, RecvError> {
+    if let Some(t)= self.popCache( ) {
+      return Ok(t)}
 
-    let mut queueRefWithMutexLock= self.channel.queue.lock( ).unwrap( );
+    let mut queueRefWithMutexLock= self.channel.lockForReceiver(&mut self.cacheReceived);
     loop {
-      match queueRefWithMutexLock._queue.pop_front( ) {
-        Some(t) => {
-          // Cache remaining items in the queue, so everytime we don't need to acquire mutex locks
-          // everytime for them.
-          if !queueRefWithMutexLock._queue.is_empty( ) {
-            // swap swaps the values at two mutable locations without deinitializing either one.
-            swap(&mut self.cache, &mut queueRefWithMutexLock._queue);}
+      if let Some(t)= queueRefWithMutexLock.popFront(&mut self.cache, self.cacheBatch) {
+        self.channel.notifySenders(queueRefWithMutexLock);
+        return Ok(t)
+      }
+
+      // If there are 0 senders or the channel has been closed.
+      if !queueRefWithMutexLock.mayReceiverSleep(&self.cache) {
+        return Err(queueRefWithMutexLock.recvError( ))}
+
+      // If there are no items available in the queue, then the OS makes the receiver thread go
+      // back to sleep. It wakes up only when there are items in the queue to consume. The item
+      // from the queue is then returned to the receiver in the 2nd iteration of the loop.
+      // Before going to sleep, the receiver thread needs to give up the mutex lock, so that it
+      // can be acquired by the sender. When the receiver wakes up, it takes back the mutex lock.
+      queueRefWithMutexLock= self.channel.condVar.wait(queueRefWithMutexLock).unwrap( );
+    }
+  }
+
+  // Like recv, but doesn't tell why there's nothing more to receive.
+  pub fn receive(&mut self) -> Option<T> {
+    self.recv( ).ok( )
+  }
+
+  // Receives the next item, but lets us inspect it before deciding whether to take it or to leave
+  // it for the next receive. The item is already out of the shared queue, so the mutex lock isn't
+  // held meanwhile.
+  pub fn recv_ref(&mut self) -> Option<MsgGuard<'_, T>> {
+    let t= self.receive( )?;
+    Some(MsgGuard { receiver: self, t: Some(t) })
+  }
+
+  // The non-blocking counterpart of receive, for async code. When there's nothing to receive yet,
+  // the task's waker is registered in the shared state, so that the next send (or the last sender
+  // going away) wakes the task up.
+  #[cfg(feature = "async")]
+  pub fn poll_recv(&mut self, cx: &mut Context<'_>) -> Poll<Option<T>> {
+    if let Some(t)= self.popCache( ) {
+      return Poll::Ready(Some(t))}
+
+    let mut queueRefWithMutexLock= self.channel.lockForReceiver(&mut self.cacheReceived);
+    match queueRefWithMutexLock.popFront(&mut self.cache, self.cacheBatch) {
+      Some(t) => {
+        self.channel.notifySenders(queueRefWithMutexLock);
+        Poll::Ready(Some(t))
+      },
+
+      None if !queueRefWithMutexLock.mayReceiverSleep(&self.cache) => Poll::Ready(None),
+
+      None => {
+        match queueRefWithMutexLock.receiverWaker {
+          Some(ref waker) if waker.will_wake(cx.waker( )) => { },
+          _ => queueRefWithMutexLock.receiverWaker= Some(cx.waker( ).clone( ))
+        }
+
+        Poll::Pending
+      }
+    }
+  }
+
+  // Never blocks. Fails with Empty if there's nothing to receive right now.
+  pub fn try_recv(&mut self) -> Result<T, TryRecvError> {
+    if let Some(t)= self.popCache( ) {
+      return Ok(t)}
+
+    let mut queueRefWithMutexLock= self.channel.lockForReceiver(&mut self.cacheReceived);
+    match queueRefWithMutexLock.popFront(&mut self.cache, self.cacheBatch) {
+      Some(t) => {
+        self.channel.notifySenders(queueRefWithMutexLock);
+        Ok(t)
+      },
+
+      None if !queueRefWithMutexLock.mayReceiverSleep(&self.cache) => {
+        Err(queueRefWithMutexLock.recvError( ).into( ))},
+
+      None => Err(TryRecvError::Empty)
+    }
+  }
+
+  // Blocks for each item, and stops once the channel is finished.
+  pub fn iter(&mut self) -> Iter<'_, T> {
+    Iter { receiver: self, error: None }
+  }
+
+  // Yields whatever can be received right now, without blocking.
+  pub fn try_iter(&mut self) -> TryIter<'_, T> {
+    TryIter { receiver: self, error: None }
+  }
+
+  // Number of items waiting to be received.
+  pub fn len(&self) -> usize {
+    self.cache.len( ) + self.channel.queue.lock( ).unwrap( )._queue.len( )
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.len( ) == 0
+  }
 
-          return Some(t)
-        },
+  // Capacity of the shared queue's buffer.
+  // NOTE - Moving the queue into the cache swaps the buffers. So, the shared queue continues with
+  // the (already allocated) buffer of the drained cache, instead of starting from scratch.
+  pub fn queue_capacity(&self) -> usize {
+    self.channel.queue.lock( ).unwrap( )._queue.capacity( )
+  }
+
+  // Applies f to each item while receiving it.
+  pub fn map<U, F>(self, f: F) -> MappedReceiver<T, F>
+    where F: FnMut(T) -> U
+  {
+    MappedReceiver { receiver: self, f }
+  }
+
+  // Skips the items for which the predicate returns false, while receiving.
+  pub fn filter<P>(self, predicate: P) -> FilteredReceiver<T, P>
+    where P: FnMut(&T) -> bool
+  {
+    FilteredReceiver { receiver: self, predicate }
+  }
+
+  // Moves upto max items into buf, preferring the cache and then taking the mutex lock at most once
+  // for the shared queue. Blocks only if there's nothing to return yet. Returns the number of items
+  // moved, which is 0 only when max is 0 or the channel is finished.
+  pub fn recv_batch(&mut self, max: usize, buf: &mut Vec<T>) -> usize {
+    let fromCache= max.min(self.cache.len( ));
+    buf.extend(self.cache.drain(..fromCache));
+    self.cacheReceived += fromCache as u64;
+
+    if fromCache == max {
+      return fromCache}
+
+    let mut queueRefWithMutexLock= self.channel.lockForReceiver(&mut self.cacheReceived);
+    loop {
+      let fromQueue= (max - fromCache).min(queueRefWithMutexLock._queue.len( ));
+      buf.extend(queueRefWithMutexLock._queue.drain(..fromQueue));
+      queueRefWithMutexLock.receivedCount += fromQueue as u64;
 
-        // If there are 0 senders.
-        None if queueRefWithMutexLock.senderCount == 0 => return None,
-  
-        // If there are no items available in the queue, then the OS makes the receiver thread go
-        // back to sleep. It wakes up only when there are items in the queue to consume. The item
-        // from the queue is then returned to the receiver in the 2nd iteration of the loop.
-        // Before going to sleep, the receiver thread needs to give up the mutex lock, so that it
-        // can be acquired by the sender. When the receiver wakes up, it takes back the mutex lock.
-        None => queueRefWithMutexLock= self.channel.condVar.wait(queueRefWithMutexLock).unwrap( )
+      let moved= fromCache + fromQueue;
+      if moved > 0 || !queueRefWithMutexLock.mayReceiverSleep(&self.cache) {
+        self.channel.notifySenders(queueRefWithMutexLock);
+        return moved
       }
+
+      queueRefWithMutexLock= self.channel.condVar.wait(queueRefWithMutexLock).unwrap( );
+    }
+  }
+
+  pub fn stats(&self) -> ChannelStats {
+    let mut stats= self.channel.queue.lock( ).unwrap( ).stats( );
+    stats.received += self.cacheReceived;
+    stats
+  }
+
+  // Clones everything which is yet to be received, in order, without consuming anything. Meant for
+  // dumping the undelivered items, say when the service is crashing. Neither the blocked senders
+  // nor the receiver are notified, since nothing changes.
+  #[cfg(feature = "serde")]
+  pub fn snapshot(&mut self) -> Vec<T>
+    where T: Clone
+  {
+    let queueRefWithMutexLock= self.channel.queue.lock( ).unwrap( );
+    self.cache.iter( ).chain(queueRefWithMutexLock._queue.iter( )).cloned( ).collect( )
+  }
+
+  // Serializes the snapshot as a sequence, using the given serializer. For example :
+  //
+  //   receiver.serialize_snapshot(&mut serde_json::Serializer::new(std::io::stderr( )))
+  #[cfg(feature = "serde")]
+  pub fn serialize_snapshot<S>(&mut self, serializer: S) -> Result<S::Ok, S::Error>
+    where T: serde::Serialize,
+          S: serde::Serializer
+  {
+    let queueRefWithMutexLock= self.channel.queue.lock( ).unwrap( );
+    serializer.collect_seq(self.cache.iter( ).chain(queueRefWithMutexLock._queue.iter( )))
+  }
+
+  // Whether receive would return None right away : no more items can arrive (all the senders are
+  // gone or the channel is closed) and nothing is left to be received.
+  pub fn is_disconnected(&self) -> bool {
+    let queueRefWithMutexLock= self.channel.queue.lock( ).unwrap( );
+    queueRefWithMutexLock.isFinished( ) && queueRefWithMutexLock._queue.is_empty( ) &&
+      self.cache.is_empty( )
+  }
+
+  pub fn same_channel(&self, other: &Self) -> bool {
+    Arc::ptr_eq(&self.channel, &other.channel)}
+
+  fn popCache(&mut self) -> Option<T> {
+    let t= self.cache.pop_front( )?;
+    self.cacheReceived += 1;
+
+    // Checking for equality (and not <=), so that the mutex lock is taken only once per crossing
+    // of the threshold, even if the shared queue turns out to be empty.
+    if self.cacheRefillThreshold > 0 && self.cache.len( ) == self.cacheRefillThreshold {
+      let mut queueRefWithMutexLock= self.channel.lockForReceiver(&mut self.cacheReceived);
+      queueRefWithMutexLock.refillCache(&mut self.cache, self.cacheBatch);
     }
+
+    Some(t)
   }
+
+  // Takes everything which is currently queued (in order) and closes the channel, so that nothing
+  // more can be sent even if some senders are still alive.
+  pub fn drain_remaining(mut self) -> Vec<T> {
+    let mut queueRefWithMutexLock= self.channel.lockForReceiver(&mut self.cacheReceived);
+    queueRefWithMutexLock.closed= true;
+
+    let queue= std::mem::take(&mut queueRefWithMutexLock._queue);
+    queueRefWithMutexLock.receivedCount += (self.cache.len( ) + queue.len( )) as u64;
+
+    self.channel.notifySenders(queueRefWithMutexLock);
+
+    self.cache.drain(..).chain(queue).collect( )
+  }
+
+  // After closing, the receiver still drains the items that are already queued and then gets None.
+  // Any further send fails.
+  pub fn close(&self) {
+    self.channel.close( )}
 }
 
-struct Queue<T> {
-  _queue: VecDeque<T>,
-  senderCount: usize
+pub struct Iter<'a, T> {
+  receiver: &'a mut Receiver<T>,
+
+  // Why the iteration stopped.
+  error: Option<RecvError>
 }
 
-struct Channel<T> {
-  queue: Mutex<Queue<T>>,
+impl<T> Iter<'_, T> {
+  // Once the iterator returns None, tells whether the channel was closed or all the senders are
+  // gone.
+  pub fn error(&self) -> Option<RecvError> {
+    self.error
+  }
+}
 
-  // Condition variables represent the ability to block a thread such that it consumes no CPU time
-  // while waiting for an event to occur.
-  condVar: Condvar
+impl<T> Iterator for Iter<'_, T> {
+  type Item = T;
+
+  fn next(&mut self) -> Option<T> {
+    self.receiver.recv( ).map_err(|error| self.error= Some(error)).ok( )
+  }
 }
 
-impl<T> Channel<T> {
-  pub fn new( ) -> (Sender<T>, Receiver<T>) {
-    let channel= Arc::new(Channel {
+pub struct TryIter<'a, T> {
+  receiver: &'a mut Receiver<T>,
 
-      queue: Mutex::new(Queue {
-        _queue: VecDeque::new( ),
-        senderCount: 1
-      }),
+  // Why the iteration stopped. None if it just ran out of items for now.
+  error: Option<RecvError>
+}
 
-      condVar: Condvar::new( )
-    });
+impl<T> TryIter<'_, T> {
+  // Once the iterator returns None, tells whether that's for good : the channel was closed or all
+  // the senders are gone.
+  pub fn error(&self) -> Option<RecvError> {
+    self.error
+  }
+}
 
-    (
-      Sender { channel: channel.clone( ) },
-      Receiver { channel, cache: VecDeque::new( ) }
-    )
+impl<T> Iterator for TryIter<'_, T> {
+  type Item = T;
+
+  fn next(&mut self) -> Option<T> {
+    match self.receiver.try_recv( ) {
+      Ok(t) => Some(t),
+
+      Err(TryRecvError::Empty) => None,
+      Err(TryRecvError::Disconnected) => { self.error= Some(RecvError::Disconnected); None },
+      Err(TryRecvError::Closed) => { self.error= Some(RecvError::Closed); None },
+      Err(TryRecvError::Poisoned(reason)) => { self.error= Some(RecvError::Poisoned(reason)); None }
+    }
   }
 }
 
-#[cfg(test)]
-mod tests {
-  use super::*;
+// Created by Receiver::recv_ref. Dropping the guard is the same as calling keep.
+pub struct MsgGuard<'a, T> {
+  receiver: &'a mut Receiver<T>,
 
-  #[test]
-  fn case_SingleSender( ) {
-    let (mut sender, mut receiver)= Channel::new( );
+  // Always Some, till the guard is consumed.
+  t: Option<T>
+}
 
-    sender.send(42);
-    assert_eq!(Some(42), receiver.receive( ));
+impl<T> MsgGuard<'_, T> {
+  pub fn take(mut self) -> T {
+    self.t.take( ).unwrap( )
   }
 
-  #[test]
-  fn case_NoSenders( ) {
-    let (mut sender, mut receiver)= Channel::<( )>::new( );
+  // Puts the item back, so that the next receive returns it again.
+  pub fn keep(self) { }
+}
 
-    drop(sender);
-    assert_eq!(None, receiver.receive( ));
+impl<T> Deref for MsgGuard<'_, T> {
+  type Target = T;
+
+  fn deref(&self) -> &T {
+    self.t.as_ref( ).unwrap( )
   }
 }
 
-/*
-  Channel flavours -
-  |
-  |- 1. Asynchronous (Unbounded) channels
-  |
-  |- 2. Synchronous (bounded) channels - The queue in this case is of constant size. When the queue
-  |     fills up, all senders remain blocked until there is again some space available in the queue.
-  |
-  |- 3. Rendezvous channels
-  |
-  |- 4. Oneshot channels.
-*/
\ No newline at end of file
+impl<T> Drop for MsgGuard<'_, T> {
+  fn drop(&mut self) {
+    let Some(t)= self.t.take( ) else {
+      return};
+
+    // The item goes in front of the cache, since everything else in there (and in the shared
+    // queue) was sent after it. NOTE - In case of a bounded channel, its space in the shared queue
+    // has already been freed up. So, for a while, the channel holds one item more than its
+    // capacity.
+    let receiver= &mut *self.receiver;
+    receiver.cache.push_front(t);
+
+    // And it's no longer received.
+    match receiver.cacheReceived.checked_sub(1) {
+      Some(cacheReceived) => receiver.cacheReceived= cacheReceived,
+      None => receiver.channel.queue.lock( ).unwrap( ).receivedCount -= 1
+    }
+  }
+}
+
+// Created by Receiver::map.
+pub struct MappedReceiver<T, F> {
+  receiver: Receiver<T>,
+  f: F
+}
+
+impl<T, U, F> MappedReceiver<T, F>
+  where F: FnMut(T) -> U
+{
+  pub fn receive(&mut self) -> Option<U> {
+    self.receiver.receive( ).map(&mut self.f)
+  }
+
+  pub fn try_recv(&mut self) -> Result<U, TryRecvError> {
+    self.receiver.try_recv( ).map(&mut self.f)
+  }
+
+  pub fn iter(&mut self) -> impl Iterator<Item = U> + '_ {
+    std::iter::from_fn(|| self.receive( ))
+  }
+
+  // Exact, since mapping is one-to-one.
+  pub fn len(&self) -> usize {
+    self.receiver.len( )
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.receiver.is_empty( )
+  }
+
+  pub fn is_disconnected(&self) -> bool {
+    self.receiver.is_disconnected( )
+  }
+
+  pub fn into_inner(self) -> Receiver<T> {
+    self.receiver
+  }
+}
+
+// Created by Receiver::filter.
+pub struct FilteredReceiver<T, P> {
+  receiver: Receiver<T>,
+  predicate: P
+}
+
+impl<T, P> FilteredReceiver<T, P>
+  where P: FnMut(&T) -> bool
+{
+  pub fn receive(&mut self) -> Option<T> {
+    loop {
+      let t= self.receiver.receive( )?;
+      if (self.predicate)(&t) {
+        return Some(t)}
+    }
+  }
+
+  // Fails with Empty if none of the items which can be received right now, pass the filter.
+  pub fn try_recv(&mut self) -> Result<T, TryRecvError> {
+    loop {
+      let t= self.receiver.try_recv( )?;
+      if (self.predicate)(&t) {
+        return Ok(t)}
+    }
+  }
+
+  pub fn iter(&mut self) -> impl Iterator<Item = T> + '_ {
+    std::iter::from_fn(|| self.receive( ))
+  }
+
+  // Only an upper bound, since some of the waiting items may not pass the filter.
+  pub fn len(&self) -> usize {
+    self.receiver.len( )
+  }
+
+  // Likewise, false doesn't mean that any of the waiting items will pass the filter.
+  pub fn is_empty(&self) -> bool {
+    self.receiver.is_empty( )
+  }
+
+  pub fn is_disconnected(&self) -> bool {
+    self.receiver.is_disconnected( )
+  }
+
+  pub fn into_inner(self) -> Receiver<T> {
+    self.receiver
+  }
+}
+
+// Items are never pinned inside the receiver's cache, so moving the receiver around is always fine.
+impl<T> Unpin for Receiver<T> { }
+
+#[cfg(feature = "async")]
+impl<T> futures_core::Stream for Receiver<T> {
+  type Item = T;
+
+  fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<T>> {
+    self.get_mut( ).poll_recv(cx)
+  }
+}
+
+// Undelivered items are dropped right away, front-to-back (i.e. in the order they were sent).
+impl<T> Drop for Receiver<T> {
+  fn drop(&mut self) {
+    let mut queueRefWithMutexLock= self.channel.queue.lock( ).unwrap( );
+    queueRefWithMutexLock.receiverCount -= 1;
+
+    let queue= std::mem::take(&mut queueRefWithMutexLock._queue);
+
+    // Blocked senders need to find out that nobody is listening anymore.
+    self.channel.notifySenders(queueRefWithMutexLock);
+
+    // Dropping the items only after the mutex lock is released, since their destructors may very
+    // well use the channel.
+    for t in self.cache.drain(..).chain(queue) {
+      drop(t)}
+  }
+}
+
+// A point-in-time view of the channel, retrievable from either of the handles.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ChannelStats {
+  // Total number of messages sent / received, since the channel was created.
+  // NOTE - Items handed out from the receiver's cache are accounted for in the shared state only the
+  // next time the receiver takes the mutex lock. So, only Receiver::stats is exact about 'received'.
+  pub sent: u64,
+  pub received: u64,
+
+  pub senderCount: usize,
+  pub receiverCount: usize,
+
+  // Maximum number of messages ever waiting in the shared queue at once.
+  pub depthHighwater: usize
+}
+
+// Returned by Sender::send when the channel is closed or the receiver is gone. The unsent value is
+// handed back.
+#[derive(Debug, PartialEq, Eq)]
+pub struct SendError<T>(pub T);
+
+impl<T> fmt::Display for SendError<T> {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "sending on a closed channel")
+  }
+}
+
+impl<T: fmt::Debug> Error for SendError<T> { }
+
+// Returned by the non-blocking sends. Either way, the unsent value is handed back.
+#[derive(Debug, PartialEq, Eq)]
+pub enum TrySendError<T> {
+  // There's no space in the queue right now.
+  Full(T),
+
+  Disconnected(T)
+}
+
+impl<T> fmt::Display for TrySendError<T> {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      TrySendError::Full(_) => write!(f, "sending on a full channel"),
+      TrySendError::Disconnected(_) => write!(f, "sending on a closed channel")
+    }
+  }
+}
+
+impl<T: fmt::Debug> Error for TrySendError<T> { }
+
+// Reported to the hook installed by SyncSender::set_backpressure_hook.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BackpressureEvent {
+  // The queue was full, so the sender started waiting.
+  Blocked { queueLength: usize },
+
+  // The sender stopped waiting, after the given duration. Either there was space in the queue, or
+  // the channel got disconnected, or the timeout elapsed.
+  Unblocked { queueLength: usize, waited: Duration }
+}
+
+// Returned by SyncSender::send_timeout. Either way, the unsent value is handed back.
+#[derive(Debug, PartialEq, Eq)]
+pub enum SendTimeoutError<T> {
+  // There was still no space in the queue when the timeout elapsed.
+  Timeout(T),
+
+  Disconnected(T)
+}
+
+impl<T> SendTimeoutError<T> {
+  pub fn into_inner(self) -> T {
+    match self {
+      SendTimeoutError::Timeout(t) | SendTimeoutError::Disconnected(t) => t
+    }
+  }
+}
+
+impl<T> fmt::Display for SendTimeoutError<T> {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      SendTimeoutError::Timeout(_) => write!(f, "timed out waiting on a full channel"),
+      SendTimeoutError::Disconnected(_) => write!(f, "sending on a closed channel")
+    }
+  }
+}
+
+impl<T: fmt::Debug> Error for SendTimeoutError<T> { }
+
+// Returned by Receiver::recv, once nothing is left to be received and no more items can arrive.
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecvError {
+  // All the senders are gone.
+  Disconnected,
+
+  // The channel was closed (from either side), though some senders may still be alive.
+  Closed,
+
+  // A sender poisoned the channel, with the given reason.
+  Poisoned(&'static str)
+}
+
+impl fmt::Display for RecvError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      RecvError::Disconnected => write!(f, "receiving on an empty and disconnected channel"),
+      RecvError::Closed => write!(f, "receiving on an empty and closed channel"),
+      RecvError::Poisoned(reason) => write!(f, "receiving on a poisoned channel : {}", reason)
+    }
+  }
+}
+
+impl Error for RecvError { }
+
+// Returned by the non-blocking receives.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TryRecvError {
+  // There's nothing to receive right now, but there might be later.
+  Empty,
+
+  Disconnected,
+
+  Closed,
+
+  Poisoned(&'static str)
+}
+
+impl From<RecvError> for TryRecvError {
+  fn from(error: RecvError) -> Self {
+    match error {
+      RecvError::Disconnected => TryRecvError::Disconnected,
+      RecvError::Closed => TryRecvError::Closed,
+      RecvError::Poisoned(reason) => TryRecvError::Poisoned(reason)
+    }
+  }
+}
+
+impl fmt::Display for TryRecvError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      TryRecvError::Empty => write!(f, "receiving on an empty channel"),
+      TryRecvError::Disconnected => write!(f, "receiving on an empty and disconnected channel"),
+      TryRecvError::Closed => write!(f, "receiving on an empty and closed channel"),
+      TryRecvError::Poisoned(reason) => write!(f, "receiving on a poisoned channel : {}", reason)
+    }
+  }
+}
+
+impl Error for TryRecvError { }
+
+struct Queue<T> {
+  _queue: VecDeque<T>,
+  senderCount: usize,
+
+  receiverCount: usize,
+
+  // Set (and never unset) by Sender::close / Receiver::close.
+  closed: bool,
+
+  // Set by Sender::poison, along with closed.
+  poisoned: Option<&'static str>,
+
+  // Maximum number of queued items, in case of a bounded channel.
+  capacity: Option<usize>,
+
+  // Statistics.
+  sentCount: u64,
+  receivedCount: u64,
+  depthHighwater: usize,
+
+  // Registered by Receiver::poll_recv, when an async receiver is waiting for items.
+  #[cfg(feature = "async")]
+  receiverWaker: Option<Waker>,
+
+  // Registered by SyncSender::poll_send, when async senders are waiting for space in the queue.
+  #[cfg(feature = "async")]
+  senderWakers: Vec<Waker>,
+
+  // Installed by SyncSender::set_backpressure_hook.
+  backpressureHook: Option<BackpressureHook>,
+
+  // Number of senders blocked, waiting for space in the queue.
+  waitingSenders: usize,
+
+  // Set for channels created by Channel::bounded_fair. Blocked senders then wait in line, each
+  // holding a ticket, and get to send in the order they started waiting.
+  fair: bool,
+  nextTicket: u64,
+
+  // Tickets of the senders waiting in line, front to back.
+  tickets: VecDeque<u64>
+}
+
+// Always std's Arc (even under loom), since loom's one can't hold a trait object.
+type BackpressureHook= std::sync::Arc<dyn Fn(BackpressureEvent) + Send + Sync>;
+
+impl<T> Queue<T> {
+  fn isDisconnectedForSenders(&self) -> bool {
+    self.closed || self.receiverCount == 0
+  }
+
+  // Whether the receiver, after finding the queue empty, should give up instead of waiting.
+  fn isFinished(&self) -> bool {
+    self.senderCount == 0 || self.closed
+  }
+
+  // Why the receiver gives up. Poisoning takes precedence, and then closing, since they are the
+  // explicit ones.
+  fn recvError(&self) -> RecvError {
+    match self.poisoned {
+      Some(reason) => RecvError::Poisoned(reason),
+      None if self.closed => RecvError::Closed,
+      None => RecvError::Disconnected
+    }
+  }
+
+  // The one and only answer to "may the receiver go to sleep now ?", checked under the mutex lock :
+  // only when there's nothing to receive (neither in the shared queue, nor in the cache) but more
+  // items may still arrive. Otherwise a notification may get lost, with the receiver sleeping
+  // forever.
+  fn mayReceiverSleep(&self, cache: &VecDeque<T>) -> bool {
+    // Items in the cache are always handed out before taking the mutex lock, and the cache is
+    // refilled only along with returning an item. So, this should never happen.
+    debug_assert!(cache.is_empty( ), "receiver is about to sleep with items in its cache");
+
+    self._queue.is_empty( ) && cache.is_empty( ) && !self.isFinished( )
+  }
+
+  fn isFull(&self) -> bool {
+    self.capacity.is_some_and(|capacity| self._queue.len( ) >= capacity)
+  }
+
+  // Whether a sender, holding the given ticket (if any), may push into the queue right now. In case
+  // of a fair channel, only the sender at the front of the line may do so. And senders which aren't
+  // in the line yet can't jump ahead of those who are.
+  fn maySend(&self, ticket: Option<u64>) -> bool {
+    !self.isFull( ) && self.tickets.front( ).copied( ) == ticket
+  }
+
+  // Puts a sender, which is about to block, at the back of the line.
+  fn takeTicket(&mut self) -> u64 {
+    let ticket= self.nextTicket;
+    self.nextTicket += 1;
+
+    self.tickets.push_back(ticket);
+    ticket
+  }
+
+  fn push(&mut self, t: T) {
+    self._queue.push_back(t);
+
+    self.sentCount += 1;
+    self.depthHighwater= self.depthHighwater.max(self._queue.len( ));
+  }
+
+  fn extend<I>(&mut self, items: I)
+    where I: IntoIterator<Item = T>
+  {
+    let lenBefore= self._queue.len( );
+    self._queue.extend(items);
+
+    self.sentCount += (self._queue.len( ) - lenBefore) as u64;
+    self.depthHighwater= self.depthHighwater.max(self._queue.len( ));
+  }
+
+  // Must be called only when the cache is empty.
+  fn popFront(&mut self, cache: &mut VecDeque<T>, cacheBatch: usize) -> Option<T> {
+    let t= self._queue.pop_front( )?;
+    self.receivedCount += 1;
+
+    // Cache remaining items in the queue, so everytime we don't need to acquire mutex locks
+    // everytime for them.
+    // For a bounded channel, we can't do this : it'll make space for 'capacity' more items in the
+    // queue.
+    if self.capacity.is_none( ) && !self._queue.is_empty( ) {
+      if self._queue.len( ) <= cacheBatch {
+        // swap swaps the values at two mutable locations without deinitializing either one.
+        swap(cache, &mut self._queue);}
+
+      // Moving everything would make a newly sent item wait behind the whole burst, while senders
+      // (and the statistics) see an empty queue. So, we only take a batch.
+      else {
+        cache.extend(self._queue.drain(..cacheBatch));}
+    }
+
+    Some(t)
+  }
+
+  // Moves items from the shared queue to the back of the cache (behind the items which are already
+  // in there, so the ordering is kept), till the cache holds cacheBatch items. Only the cache of an
+  // unbounded channel is ever non-empty, so this never makes space in a bounded queue.
+  fn refillCache(&mut self, cache: &mut VecDeque<T>, cacheBatch: usize) {
+    let count= cacheBatch.saturating_sub(cache.len( )).min(self._queue.len( ));
+    cache.extend(self._queue.drain(..count));
+  }
+
+  fn stats(&self) -> ChannelStats {
+    ChannelStats {
+      sent: self.sentCount,
+      received: self.receivedCount,
+      senderCount: self.senderCount,
+      receiverCount: self.receiverCount,
+      depthHighwater: self.depthHighwater
+    }
+  }
+}
+
+struct Channel<T> {
+  queue: Mutex<Queue<T>>,
+
+  // Condition variables represent the ability to block a thread such that it consumes no CPU time
+  // while waiting for an event to occur.
+  condVar: Condvar,
+
+  // Senders of a bounded channel wait on this one, till there's space in the queue.
+  senderCondVar: Condvar
+}
+
+impl<T> Channel<T> {
+  #[allow(clippy::new_ret_no_self)]
+  pub fn new( ) -> (Sender<T>, Receiver<T>) {
+    Self::create(None, 0)
+  }
+
+  // Like new, but the receiver moves at most cacheBatch items into its cache per mutex lock
+  // acquisition. usize::MAX (which is what new uses) means everything, while 0 disables the cache.
+  pub fn with_cache_batch(cacheBatch: usize) -> (Sender<T>, Receiver<T>) {
+    Self::with_cache_refill(cacheBatch, 0)
+  }
+
+  // Like with_cache_batch, but once the cache drains down to refillThreshold items, the receiver
+  // tops it up from the shared queue (taking the mutex lock), instead of waiting for it to run dry.
+  // So, the senders and the statistics keep seeing the receiver's progress during a long burst.
+  pub fn with_cache_refill(cacheBatch: usize, refillThreshold: usize) -> (Sender<T>, Receiver<T>) {
+    let (sender, mut receiver)= Self::create(None, 0);
+    receiver.cacheBatch= cacheBatch;
+    receiver.cacheRefillThreshold= refillThreshold;
+
+    (sender, receiver)
+  }
+
+  // A channel where receive always yields the highest priority pending message.
+  pub fn priority( ) -> (priority::PrioritySender<T>, priority::PriorityReceiver<T>) {
+    priority::create( )
+  }
+
+  // A channel where every receiver sees every message. Only the last 'capacity' messages are
+  // retained. Panics if the capacity is 0.
+  pub fn broadcast(capacity: usize)
+    -> (broadcast::BroadcastSender<T>, broadcast::BroadcastReceiver<T>)
+  {
+    broadcast::create(capacity)
+  }
+
+  // Panics if the capacity is 0, since rendezvous channels aren't supported.
+  pub fn bounded(capacity: usize) -> (SyncSender<T>, Receiver<T>) {
+    assert!(capacity > 0, "capacity of a bounded channel must be non-zero");
+
+    let (sender, receiver)= Self::create(Some(capacity), capacity);
+
+    let syncSender= SyncSender {
+      sender,
+
+      #[cfg(feature = "async")]
+      pending: None
+    };
+    (syncSender, receiver)
+  }
+
+  // Like bounded, but blocked senders get to send in the order they started waiting. So, no sender
+  // gets starved by the others. NOTE - Async senders don't wait in line, though they never jump
+  // ahead of those who do.
+  pub fn bounded_fair(capacity: usize) -> (SyncSender<T>, Receiver<T>) {
+    let (syncSender, receiver)= Self::bounded(capacity);
+    syncSender.sender.channel.queue.lock( ).unwrap( ).fair= true;
+
+    (syncSender, receiver)
+  }
+
+  // Like new, but the shared queue and the receiver's cache are preallocated to hold initial items.
+  // This avoids latency spikes due to reallocations during bursts.
+  pub fn with_capacity(initial: usize) -> (Sender<T>, Receiver<T>) {
+    Self::create(None, initial)
+  }
+
+  fn create(capacity: Option<usize>, preallocate: usize) -> (Sender<T>, Receiver<T>) {
+    let channel= Arc::new(Channel {
+
+      queue: Mutex::new(Queue {
+        _queue: VecDeque::with_capacity(preallocate),
+        senderCount: 1,
+        receiverCount: 1,
+        closed: false,
+        poisoned: None,
+        capacity,
+
+        sentCount: 0,
+        receivedCount: 0,
+        depthHighwater: 0,
+
+        #[cfg(feature = "async")]
+        receiverWaker: None,
+
+        #[cfg(feature = "async")]
+        senderWakers: Vec::new( ),
+
+        backpressureHook: None,
+
+        waitingSenders: 0,
+
+        fair: false,
+        nextTicket: 0,
+        tickets: VecDeque::new( )
+      }),
+
+      condVar: Condvar::new( ),
+      senderCondVar: Condvar::new( )
+    });
+
+    (
+      Sender { channel: channel.clone( ) },
+      Receiver {
+        channel,
+        cache: VecDeque::with_capacity(preallocate),
+        cacheBatch: usize::MAX,
+        cacheRefillThreshold: 0,
+        cacheReceived: 0
+      }
+    )
+  }
+
+  fn lockForReceiver(&self, cacheReceived: &mut u64) -> MutexGuard<'_, Queue<T>> {
+    let mut queueRefWithMutexLock= self.queue.lock( ).unwrap( );
+    queueRefWithMutexLock.receivedCount += std::mem::take(cacheReceived);
+    queueRefWithMutexLock
+  }
+
+  // Closing is idempotent. Everyone who might be blocked on the channel is woken up, so that they
+  // can observe the closed state.
+  fn close(&self) {
+    let mut queueRefWithMutexLock= self.queue.lock( ).unwrap( );
+    queueRefWithMutexLock.closed= true;
+
+    self.notifyReceiver(queueRefWithMutexLock);
+    self.notifySenders(self.queue.lock( ).unwrap( ));
+  }
+
+  fn poison(&self, reason: &'static str) {
+    let mut queueRefWithMutexLock= self.queue.lock( ).unwrap( );
+    queueRefWithMutexLock.poisoned.get_or_insert(reason);
+    queueRefWithMutexLock.closed= true;
+
+    self.notifyReceiver(queueRefWithMutexLock);
+    self.notifySenders(self.queue.lock( ).unwrap( ));
+  }
+
+  #[cfg(feature = "async")]
+  fn pollSend(&self, cx: &mut Context<'_>, item: &mut Option<T>)
+    -> Poll<Result<( ), SendError<T>>>
+  {
+    let Some(t)= item.take( ) else {
+      return Poll::Ready(Ok(( )))};
+
+    let mut queueRefWithMutexLock= self.queue.lock( ).unwrap( );
+
+    if queueRefWithMutexLock.isDisconnectedForSenders( ) {
+      return Poll::Ready(Err(SendError(t)))}
+
+    if !queueRefWithMutexLock.maySend(None) {
+      *item= Some(t);
+
+      if !queueRefWithMutexLock.senderWakers.iter( ).any(|waker| waker.will_wake(cx.waker( ))) {
+        queueRefWithMutexLock.senderWakers.push(cx.waker( ).clone( ))}
+
+      return Poll::Pending
+    }
+
+    queueRefWithMutexLock.push(t);
+
+    self.notifyReceiver(queueRefWithMutexLock);
+    Poll::Ready(Ok(( )))
+  }
+
+  // Drops the mutex lock first, so that when the receiver wakes up, it can immediately take the
+  // mutex lock. Then wakes up the receiver, whether it's blocked on the condvar or it's an async
+  // task.
+  fn notifyReceiver(&self, mut queueRefWithMutexLock: MutexGuard<'_, Queue<T>>) {
+    #[cfg(feature = "async")]
+    let receiverWaker= queueRefWithMutexLock.receiverWaker.take( );
+
+    drop(queueRefWithMutexLock);
+    self.condVar.notify_one( );
+
+    #[cfg(feature = "async")]
+    if let Some(receiverWaker)= receiverWaker {
+      receiverWaker.wake( )}
+  }
+
+  // Lets the senders of a bounded channel know that there's space in the queue now (or that they
+  // should give up). It's a no-op for unbounded channels, since their senders never wait.
+  fn notifySenders(&self, mut queueRefWithMutexLock: MutexGuard<'_, Queue<T>>) {
+    if queueRefWithMutexLock.capacity.is_none( ) {
+      return}
+
+    #[cfg(feature = "async")]
+    let senderWakers= std::mem::take(&mut queueRefWithMutexLock.senderWakers);
+
+    drop(queueRefWithMutexLock);
+    self.senderCondVar.notify_all( );
+
+    #[cfg(feature = "async")]
+    senderWakers.into_iter( ).for_each(Waker::wake);
+  }
+}
+
+pub mod priority {
+  use super::{SendError, sync::{Arc, Mutex, Condvar}};
+  use std::{collections::BinaryHeap, cmp::Ordering};
+
+  pub struct PrioritySender<T> {
+    channel: Arc<PriorityChannel<T>>
+  }
+
+  impl<T> PrioritySender<T> {
+    // Sends with the lowest priority (0).
+    pub fn send(&self, t: T) -> Result<( ), SendError<T>> {
+      self.send_with_priority(0, t)
+    }
+
+    // Messages with a higher priority are received first. Messages with the same priority are
+    // received in the order they were sent.
+    pub fn send_with_priority(&self, priority: u8, t: T) -> Result<( ), SendError<T>> {
+      let mut heapRefWithMutexLock= self.channel.heap.lock( ).unwrap( );
+      if heapRefWithMutexLock.receiverCount == 0 {
+        return Err(SendError(t))}
+
+      let sequence= heapRefWithMutexLock.nextSequence;
+      heapRefWithMutexLock.nextSequence += 1;
+
+      heapRefWithMutexLock._heap.push(Entry { priority, sequence, t });
+
+      drop(heapRefWithMutexLock);
+      self.channel.condVar.notify_one( );
+      Ok(( ))
+    }
+  }
+
+  impl<T> Clone for PrioritySender<T> {
+    fn clone(&self) -> Self {
+      self.channel.heap.lock( ).unwrap( ).senderCount += 1;
+
+      Self {
+        channel: Arc::clone(&self.channel)
+      }
+    }
+  }
+
+  impl<T> Drop for PrioritySender<T> {
+    fn drop(&mut self) {
+      let mut heapRefWithMutexLock= self.channel.heap.lock( ).unwrap( );
+      heapRefWithMutexLock.senderCount -= 1;
+
+      let noSenders= (heapRefWithMutexLock.senderCount == 0);
+
+      drop(heapRefWithMutexLock);
+
+      if noSenders {
+        self.channel.condVar.notify_one( )}
+    }
+  }
+
+  // NOTE - Unlike the regular Receiver, there's no cache here. Items cached while the highest
+  // priority was X would be handed out before an item with priority > X sent afterwards.
+  pub struct PriorityReceiver<T> {
+    channel: Arc<PriorityChannel<T>>
+  }
+
+  impl<T> PriorityReceiver<T> {
+    pub fn receive(&mut self) -> Option<T> {
+      let mut heapRefWithMutexLock= self.channel.heap.lock( ).unwrap( );
+      loop {
+        match heapRefWithMutexLock._heap.pop( ) {
+          Some(entry) => return Some(entry.t),
+
+          None if heapRefWithMutexLock.senderCount == 0 => return None,
+
+          None => heapRefWithMutexLock= self.channel.condVar.wait(heapRefWithMutexLock).unwrap( )
+        }
+      }
+    }
+  }
+
+  impl<T> Drop for PriorityReceiver<T> {
+    fn drop(&mut self) {
+      self.channel.heap.lock( ).unwrap( ).receiverCount -= 1;
+    }
+  }
+
+  // The sequence number breaks ties between entries with the same priority, so that the one sent
+  // first is popped first from the (max) BinaryHeap.
+  struct Entry<T> {
+    priority: u8,
+    sequence: u64,
+    t: T
+  }
+
+  impl<T> Ord for Entry<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+      self.priority.cmp(&other.priority)
+        .then_with(|| other.sequence.cmp(&self.sequence))
+    }
+  }
+
+  impl<T> PartialOrd for Entry<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+      Some(self.cmp(other))
+    }
+  }
+
+  impl<T> PartialEq for Entry<T> {
+    fn eq(&self, other: &Self) -> bool {
+      self.cmp(other) == Ordering::Equal
+    }
+  }
+
+  impl<T> Eq for Entry<T> { }
+
+  struct Heap<T> {
+    _heap: BinaryHeap<Entry<T>>,
+    nextSequence: u64,
+
+    senderCount: usize,
+    receiverCount: usize
+  }
+
+  struct PriorityChannel<T> {
+    heap: Mutex<Heap<T>>,
+    condVar: Condvar
+  }
+
+  pub(crate) fn create<T>( ) -> (PrioritySender<T>, PriorityReceiver<T>) {
+    let channel= Arc::new(PriorityChannel {
+
+      heap: Mutex::new(Heap {
+        _heap: BinaryHeap::new( ),
+        nextSequence: 0,
+        senderCount: 1,
+        receiverCount: 1
+      }),
+
+      condVar: Condvar::new( )
+    });
+
+    (
+      PrioritySender { channel: channel.clone( ) },
+      PriorityReceiver { channel }
+    )
+  }
+}
+
+/*
+  Every message is stored only once, in a shared ring of slots. Each receiver has its own cursor
+  pointing to the sequence number of the next message it wants to read, and clones the message out
+  of the ring only when it actually reads it.
+
+  Sequence numbers keep increasing, while the ring keeps only the last 'capacity' messages. So, a
+  receiver whose cursor points before the oldest retained message has lagged behind : some messages
+  were overwritten before it could read them.
+*/
+pub mod broadcast {
+  use super::{SendError, sync::{Arc, Mutex, Condvar}};
+  use std::{collections::VecDeque, fmt, error::Error};
+
+  pub struct BroadcastSender<T> {
+    channel: Arc<BroadcastChannel<T>>
+  }
+
+  impl<T> BroadcastSender<T> {
+    // Overwrites the oldest retained message, if the ring is full. Fails only if there are no
+    // receivers.
+    pub fn send(&self, t: T) -> Result<( ), SendError<T>> {
+      let mut ringRefWithMutexLock= self.channel.ring.lock( ).unwrap( );
+      if ringRefWithMutexLock.receiverCount == 0 {
+        return Err(SendError(t))}
+
+      if ringRefWithMutexLock.slots.len( ) == ringRefWithMutexLock.capacity {
+        ringRefWithMutexLock.slots.pop_front( );
+        ringRefWithMutexLock.firstSequence += 1;
+      }
+      ringRefWithMutexLock.slots.push_back(t);
+
+      drop(ringRefWithMutexLock);
+      self.channel.condVar.notify_all( );
+      Ok(( ))
+    }
+
+    // The new receiver sees only the messages sent from now on.
+    pub fn subscribe(&self) -> BroadcastReceiver<T> {
+      let mut ringRefWithMutexLock= self.channel.ring.lock( ).unwrap( );
+      ringRefWithMutexLock.receiverCount += 1;
+
+      let cursor= ringRefWithMutexLock.nextSequence( );
+
+      drop(ringRefWithMutexLock);
+
+      BroadcastReceiver { channel: Arc::clone(&self.channel), cursor }
+    }
+  }
+
+  impl<T> Clone for BroadcastSender<T> {
+    fn clone(&self) -> Self {
+      self.channel.ring.lock( ).unwrap( ).senderCount += 1;
+
+      Self {
+        channel: Arc::clone(&self.channel)
+      }
+    }
+  }
+
+  impl<T> Drop for BroadcastSender<T> {
+    fn drop(&mut self) {
+      let mut ringRefWithMutexLock= self.channel.ring.lock( ).unwrap( );
+      ringRefWithMutexLock.senderCount -= 1;
+
+      let noSenders= (ringRefWithMutexLock.senderCount == 0);
+
+      drop(ringRefWithMutexLock);
+
+      if noSenders {
+        self.channel.condVar.notify_all( )}
+    }
+  }
+
+  pub struct BroadcastReceiver<T> {
+    channel: Arc<BroadcastChannel<T>>,
+
+    // Sequence number of the next message to read.
+    cursor: u64
+  }
+
+  impl<T: Clone> BroadcastReceiver<T> {
+    // After reporting Lagged, the receiver resumes from the oldest retained message.
+    pub fn receive(&mut self) -> Result<T, BroadcastRecvError> {
+      let mut ringRefWithMutexLock= self.channel.ring.lock( ).unwrap( );
+      loop {
+        if self.cursor < ringRefWithMutexLock.firstSequence {
+          let missed= ringRefWithMutexLock.firstSequence - self.cursor;
+          self.cursor= ringRefWithMutexLock.firstSequence;
+
+          return Err(BroadcastRecvError::Lagged(missed))
+        }
+
+        let index= (self.cursor - ringRefWithMutexLock.firstSequence) as usize;
+        if let Some(t)= ringRefWithMutexLock.slots.get(index) {
+          self.cursor += 1;
+          return Ok(t.clone( ))
+        }
+
+        if ringRefWithMutexLock.senderCount == 0 {
+          return Err(BroadcastRecvError::Disconnected)}
+
+        ringRefWithMutexLock= self.channel.condVar.wait(ringRefWithMutexLock).unwrap( );
+      }
+    }
+  }
+
+  impl<T> Drop for BroadcastReceiver<T> {
+    fn drop(&mut self) {
+      self.channel.ring.lock( ).unwrap( ).receiverCount -= 1;
+    }
+  }
+
+  #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+  pub enum BroadcastRecvError {
+    // The receiver fell behind, and these many messages were overwritten before it could read them.
+    Lagged(u64),
+
+    // All the senders are gone, and every retained message has been read.
+    Disconnected
+  }
+
+  impl fmt::Display for BroadcastRecvError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+      match self {
+        BroadcastRecvError::Lagged(missed) =>
+          write!(f, "receiver lagged behind by {} messages", missed),
+
+        BroadcastRecvError::Disconnected => write!(f, "receiving on a closed channel")
+      }
+    }
+  }
+
+  impl Error for BroadcastRecvError { }
+
+  struct Ring<T> {
+    slots: VecDeque<T>,
+    capacity: usize,
+
+    // Sequence number of the oldest retained message (slots[0]).
+    firstSequence: u64,
+
+    senderCount: usize,
+    receiverCount: usize
+  }
+
+  impl<T> Ring<T> {
+    fn nextSequence(&self) -> u64 {
+      self.firstSequence + self.slots.len( ) as u64
+    }
+  }
+
+  struct BroadcastChannel<T> {
+    ring: Mutex<Ring<T>>,
+
+    // Multiple receivers may be waiting on it, so it's always notify_all.
+    condVar: Condvar
+  }
+
+  pub(crate) fn create<T>(capacity: usize) -> (BroadcastSender<T>, BroadcastReceiver<T>) {
+    assert!(capacity > 0, "capacity of a broadcast channel must be non-zero");
+
+    let channel= Arc::new(BroadcastChannel {
+
+      ring: Mutex::new(Ring {
+        slots: VecDeque::with_capacity(capacity),
+        capacity,
+        firstSequence: 0,
+        senderCount: 1,
+        receiverCount: 1
+      }),
+
+      condVar: Condvar::new( )
+    });
+
+    (
+      BroadcastSender { channel: channel.clone( ) },
+      BroadcastReceiver { channel, cursor: 0 }
+    )
+  }
+}
+
+/*
+  A lock-free Single Producer Single Consumer channel, backed by a fixed size ring buffer.
+
+  head is the index of the next slot to read from, and is written only by the receiver. tail is the
+  index of the next slot to write to, and is written only by the sender. Both of them only ever
+  increase (wrapping around on overflow), and are masked to get the actual slot index. So,
+  tail - head is the number of items in the ring.
+
+  The sender writes the item into the slot and then publishes it by storing tail with Release
+  ordering. The receiver loads tail with Acquire ordering, which makes the item write visible to it.
+  The same happens the other way around for head, so that the sender never overwrites a slot the
+  receiver is still reading from.
+*/
+pub mod spsc {
+  use super::{
+    SendError, TrySendError, TryRecvError,
+    sync::{Arc, Mutex, Condvar, UnsafeCell, AtomicUsize, AtomicBool, fence, Ordering, spin_loop}
+  };
+  use std::mem::MaybeUninit;
+
+  // Number of times a blocking operation retries before it falls back to parking the thread.
+  #[cfg(not(loom))]
+  const SPIN_LIMIT: usize= 64;
+
+  // Under loom, every spin multiplies the interleavings to explore. A single one still covers both
+  // the spinning and the parking paths.
+  #[cfg(loom)]
+  const SPIN_LIMIT: usize= 1;
+
+  // The capacity is rounded up to a power of two, so that a slot index can be derived from head /
+  // tail with a cheap bitmask instead of a modulo. Panics if the capacity is 0.
+  pub fn channel<T>(capacity: usize) -> (Sender<T>, Receiver<T>) {
+    assert!(capacity > 0, "capacity of an spsc channel must be non-zero");
+
+    let capacity= capacity.next_power_of_two( );
+
+    let ring= Arc::new(Ring {
+      slots: (0..capacity).map(|_| UnsafeCell::new(MaybeUninit::uninit( ))).collect( ),
+      mask: capacity - 1,
+
+      head: AtomicUsize::new(0),
+      tail: AtomicUsize::new(0),
+
+      senderAlive: AtomicBool::new(true),
+      receiverAlive: AtomicBool::new(true),
+
+      parkingLot: Mutex::new(( )),
+      condVar: Condvar::new( ),
+      senderParked: AtomicBool::new(false),
+      receiverParked: AtomicBool::new(false)
+    });
+
+    (Sender { ring: ring.clone( ) }, Receiver { ring })
+  }
+
+  pub struct Sender<T> {
+    ring: Arc<Ring<T>>
+  }
+
+  impl<T> Sender<T> {
+    pub fn try_send(&mut self, t: T) -> Result<( ), TrySendError<T>> {
+      if !self.ring.receiverAlive.load(Ordering::Acquire) {
+        return Err(TrySendError::Disconnected(t))}
+
+      // Only we write tail, so a Relaxed load is enough.
+      let tail= self.ring.tail.load(Ordering::Relaxed);
+      let head= self.ring.head.load(Ordering::Acquire);
+
+      if tail.wrapping_sub(head) == self.ring.slots.len( ) {
+        return Err(TrySendError::Full(t))}
+
+      // SAFETY : The slot is empty (the receiver is done reading from it) and only the sender ever
+      // writes to a slot.
+      self.ring.withSlot(tail, |slot| unsafe { (*slot).write(t); });
+      self.ring.tail.store(tail.wrapping_add(1), Ordering::Release);
+
+      self.ring.unpark(&self.ring.receiverParked);
+      Ok(( ))
+    }
+
+    // Spins for a while if the ring is full, and then parks the thread till the receiver makes
+    // some space.
+    pub fn send(&mut self, mut t: T) -> Result<( ), SendError<T>> {
+      let mut spins= 0;
+      loop {
+        match self.try_send(t) {
+          Ok(( )) => return Ok(( )),
+          Err(TrySendError::Disconnected(_t)) => return Err(SendError(_t)),
+
+          Err(TrySendError::Full(_t)) => {
+            t= _t;
+
+            if spins < SPIN_LIMIT {
+              spins += 1;
+              spin_loop( );
+            }
+            else {
+              let ring= &self.ring;
+              ring.park(&ring.senderParked, || {
+                let isFull= ring.tail.load(Ordering::Relaxed)
+                  .wrapping_sub(ring.head.load(Ordering::Acquire)) == ring.slots.len( );
+
+                !isFull || !ring.receiverAlive.load(Ordering::Acquire)
+              });
+            }
+          }
+        }
+      }
+    }
+  }
+
+  impl<T> Drop for Sender<T> {
+    fn drop(&mut self) {
+      self.ring.senderAlive.store(false, Ordering::Release);
+      self.ring.unpark(&self.ring.receiverParked);
+    }
+  }
+
+  pub struct Receiver<T> {
+    ring: Arc<Ring<T>>
+  }
+
+  impl<T> Receiver<T> {
+    pub fn try_recv(&mut self) -> Result<T, TryRecvError> {
+      // Only we write head, so a Relaxed load is enough.
+      let head= self.ring.head.load(Ordering::Relaxed);
+
+      if head == self.ring.tail.load(Ordering::Acquire) {
+        if self.ring.senderAlive.load(Ordering::Acquire) {
+          return Err(TryRecvError::Empty)}
+
+        // The sender may have sent something right before going away.
+        if head == self.ring.tail.load(Ordering::Acquire) {
+          return Err(TryRecvError::Disconnected)}
+      }
+
+      // SAFETY : The slot has been initialized by the sender (which is visible to us thanks to the
+      // Acquire load of tail), and the sender won't touch it till we move head past it.
+      let t= self.ring.withSlot(head, |slot| unsafe { (*slot).assume_init_read( ) });
+      self.ring.head.store(head.wrapping_add(1), Ordering::Release);
+
+      self.ring.unpark(&self.ring.senderParked);
+      Ok(t)
+    }
+
+    // Spins for a while if the ring is empty, and then parks the thread till the sender sends
+    // something. Returns None once the sender is gone and the ring is empty.
+    pub fn receive(&mut self) -> Option<T> {
+      let mut spins= 0;
+      loop {
+        match self.try_recv( ) {
+          Ok(t) => return Some(t),
+          Err(TryRecvError::Disconnected | TryRecvError::Closed | TryRecvError::Poisoned(_)) => {
+            return None},
+
+          Err(TryRecvError::Empty) => {
+            if spins < SPIN_LIMIT {
+              spins += 1;
+              spin_loop( );
+            }
+            else {
+              let ring= &self.ring;
+              ring.park(&ring.receiverParked, || {
+                ring.head.load(Ordering::Relaxed) != ring.tail.load(Ordering::Acquire) ||
+                  !ring.senderAlive.load(Ordering::Acquire)
+              });
+            }
+          }
+        }
+      }
+    }
+  }
+
+  impl<T> Drop for Receiver<T> {
+    fn drop(&mut self) {
+      self.ring.receiverAlive.store(false, Ordering::Release);
+      self.ring.unpark(&self.ring.senderParked);
+    }
+  }
+
+  struct Ring<T> {
+    slots: Box<[UnsafeCell<MaybeUninit<T>>]>,
+    mask: usize,
+
+    head: AtomicUsize,
+    tail: AtomicUsize,
+
+    senderAlive: AtomicBool,
+    receiverAlive: AtomicBool,
+
+    // Fallback for the blocking operations, once spinning didn't help. Whoever parks, sets its
+    // flag, so that the other side knows that it needs to take the (otherwise unused) mutex lock
+    // and notify the condvar.
+    parkingLot: Mutex<( )>,
+    condVar: Condvar,
+    senderParked: AtomicBool,
+    receiverParked: AtomicBool
+  }
+
+  // SAFETY : Each slot is accessed by only one of the sender / receiver at a time, as coordinated
+  // by head and tail. Items are moved across threads, so T needs to be Send.
+  unsafe impl<T: Send> Sync for Ring<T> { }
+  unsafe impl<T: Send> Send for Ring<T> { }
+
+  impl<T> Ring<T> {
+    fn withSlot<R>(&self, index: usize, f: impl FnOnce(*mut MaybeUninit<T>) -> R) -> R {
+      self.slots[index & self.mask].with_mut(f)
+    }
+
+    // Parks the thread till canProceed returns true. It's checked after setting the parked flag
+    // and while holding the mutex lock, so that a wake up can't slip in between the check and the
+    // wait.
+    fn park(&self, parked: &AtomicBool, canProceed: impl Fn( ) -> bool) {
+      let mut guard= self.parkingLot.lock( ).unwrap( );
+
+      parked.store(true, Ordering::SeqCst);
+      fence(Ordering::SeqCst);
+
+      while !canProceed( ) {
+        guard= self.condVar.wait(guard).unwrap( );}
+
+      parked.store(false, Ordering::SeqCst);
+    }
+
+    // The SeqCst fence pairs with the one in park : either the parked side observes our update
+    // while checking canProceed, or we observe its parked flag here.
+    fn unpark(&self, parked: &AtomicBool) {
+      fence(Ordering::SeqCst);
+
+      if parked.load(Ordering::SeqCst) {
+        drop(self.parkingLot.lock( ).unwrap( ));
+        self.condVar.notify_all( );
+      }
+    }
+  }
+
+  impl<T> Drop for Ring<T> {
+    fn drop(&mut self) {
+      // We have &mut self, so nobody else is touching head and tail. (loom's atomics don't have
+      // get_mut.)
+      let tail= self.tail.load(Ordering::Relaxed);
+      let mut head= self.head.load(Ordering::Relaxed);
+
+      // Dropping the items which were never received.
+      while head != tail {
+        // SAFETY : Slots between head and tail are initialized, and nobody else can access them.
+        self.withSlot(head, |slot| unsafe { (*slot).assume_init_drop( ); });
+        head= head.wrapping_add(1);
+      }
+    }
+  }
+}
+
+#[cfg(all(test, not(loom)))]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn case_SingleSender( ) {
+    let (mut sender, mut receiver)= Channel::new( );
+
+    sender.send(42).unwrap( );
+    assert_eq!(Some(42), receiver.receive( ));
+  }
+
+  #[test]
+  fn case_NoSenders( ) {
+    let (mut sender, mut receiver)= Channel::<( )>::new( );
+
+    drop(sender);
+    assert_eq!(Err(RecvError::Disconnected), receiver.recv( ));
+    assert_eq!(None, receiver.receive( ));
+  }
+
+  #[test]
+  fn case_CloseFromSender( ) {
+    let (sender, mut receiver)= Channel::new( );
+
+    sender.send(1).unwrap( );
+    sender.send(2).unwrap( );
+    sender.close( );
+    sender.close( ); // Closing is idempotent.
+
+    assert_eq!(Err(SendError(3)), sender.send(3));
+
+    // Already queued items are still delivered, even though the sender is alive.
+    assert_eq!(Some(1), receiver.receive( ));
+    assert_eq!(Ok(2), receiver.recv( ));
+    assert_eq!(Err(RecvError::Closed), receiver.recv( ));
+    assert_eq!(Err(TryRecvError::Closed), receiver.try_recv( ));
+  }
+
+  #[test]
+  fn case_CloseFromReceiver( ) {
+    let (sender, mut receiver)= Channel::new( );
+
+    let handle= std::thread::spawn(move || {
+      let mut sent= 0;
+      while sender.send(sent).is_ok( ) {
+        sent += 1;
+      }
+      sent
+    });
+
+    assert_eq!(Some(0), receiver.receive( ));
+    receiver.close( );
+
+    let sent= handle.join( ).unwrap( );
+    assert!(sent >= 1);
+  }
+
+  #[test]
+  fn case_BatchAndSingleOperationsInterleaved( ) {
+    let (sender, mut receiver)= Channel::new( );
+
+    sender.send(0).unwrap( );
+    sender.send_all(1..4).unwrap( );
+
+    assert_eq!(Some(0), receiver.receive( )); // Moves 1, 2 and 3 into the cache.
+
+    sender.send_all(vec!{ 4, 5 }).unwrap( );
+    sender.send(6).unwrap( );
+
+    let mut buf= Vec::new( );
+    assert_eq!(4, receiver.recv_batch(4, &mut buf));
+    assert_eq!(vec!{ 1, 2, 3, 4 }, buf);
+
+    assert_eq!(Some(5), receiver.receive( ));
+
+    drop(sender);
+    buf.clear( );
+    assert_eq!(1, receiver.recv_batch(10, &mut buf));
+    assert_eq!(vec!{ 6 }, buf);
+    assert_eq!(0, receiver.recv_batch(10, &mut buf));
+  }
+
+  #[test]
+  fn case_BatchOf10kArrivesInOrder( ) {
+    let (sender, mut receiver)= Channel::new( );
+
+    let handle= std::thread::spawn(move || sender.send_all(0..10_000).is_ok( ));
+
+    let mut buf= Vec::new( );
+    while receiver.recv_batch(256, &mut buf) > 0 { }
+
+    assert!(handle.join( ).unwrap( ));
+    assert_eq!((0..10_000).collect::<Vec<_>>( ), buf);
+  }
+
+  #[test]
+  fn case_WeakSenderUpgrade( ) {
+    let (sender, mut receiver)= Channel::new( );
+    let weakSender= sender.downgrade( );
+
+    let upgradedSender= weakSender.upgrade( ).unwrap( );
+    upgradedSender.send(1).unwrap( );
+
+    drop(sender);
+    drop(upgradedSender);
+
+    assert!(weakSender.upgrade( ).is_none( ));
+
+    // The weak sender doesn't keep the channel connected.
+    assert_eq!(Some(1), receiver.receive( ));
+    assert_eq!(None, receiver.receive( ));
+  }
+
+  #[test]
+  fn case_WeakSenderUpgradeFailsWithoutReceiver( ) {
+    let (sender, receiver)= Channel::<( )>::new( );
+    let weakSender= sender.downgrade( );
+
+    drop(receiver);
+
+    assert!(weakSender.upgrade( ).is_none( ));
+    assert_eq!(Err(SendError(( ))), sender.send(( )));
+  }
+
+  #[cfg(feature = "async")]
+  #[test]
+  fn case_StreamYieldsUntilDisconnect( ) {
+    use futures::StreamExt;
+
+    let (sender, receiver)= Channel::new( );
+
+    let handle= std::thread::spawn(move || {
+      for i in 0..100 {
+        sender.send(i).unwrap( );
+
+        if i % 10 == 0 {
+          std::thread::sleep(std::time::Duration::from_millis(1))}
+      }
+    });
+
+    let received= futures::executor::block_on(receiver.collect::<Vec<_>>( ));
+    handle.join( ).unwrap( );
+
+    assert_eq!((0..100).collect::<Vec<_>>( ), received);
+  }
+
+  #[cfg(feature = "async")]
+  #[test]
+  fn case_PollRecvCoexistsWithBlockingReceive( ) {
+    let (sender, mut receiver)= Channel::new( );
+
+    sender.send(1).unwrap( );
+    assert_eq!(Some(1), receiver.receive( ));
+
+    let handle= std::thread::spawn(move || {
+      std::thread::sleep(std::time::Duration::from_millis(50));
+      sender.send(2).unwrap( );
+    });
+
+    let received= futures::executor::block_on(std::future::poll_fn(|cx| receiver.poll_recv(cx)));
+    assert_eq!(Some(2), received);
+
+    handle.join( ).unwrap( );
+    assert_eq!(None, receiver.receive( ));
+  }
+
+  #[test]
+  fn case_BoundedSendBlocksWhenFull( ) {
+    let (sender, mut receiver)= Channel::bounded(2);
+
+    sender.send(0).unwrap( );
+    sender.send(1).unwrap( );
+
+    let handle= std::thread::spawn(move || {
+      sender.send(2).unwrap( ); // Blocks till the receiver makes space.
+      sender
+    });
+
+    std::thread::sleep(std::time::Duration::from_millis(50));
+    assert!(!handle.is_finished( ));
+
+    assert_eq!(Some(0), receiver.receive( ));
+    let sender= handle.join( ).unwrap( );
+
+    assert_eq!(Some(1), receiver.receive( ));
+    assert_eq!(Some(2), receiver.receive( ));
+
+    drop(receiver);
+    assert_eq!(Err(SendError(3)), sender.send(3));
+  }
+
+  #[cfg(feature = "async")]
+  #[test]
+  fn case_AsyncBoundedSenderAppliesBackpressure( ) {
+    use futures::SinkExt;
+
+    let (mut sender, mut receiver)= Channel::bounded(1);
+
+    let consumer= std::thread::spawn(move || {
+      let mut received= Vec::new( );
+      while let Some(item)= receiver.receive( ) {
+        std::thread::sleep(std::time::Duration::from_micros(200));
+        received.push(item);
+      }
+      received
+    });
+
+    let mut pendingCount= 0;
+    futures::executor::block_on(async {
+      for i in 0..100 {
+        let mut item= Some(i);
+        std::future::poll_fn(|cx| {
+          let poll= sender.poll_send(cx, &mut item);
+          if poll.is_pending( ) {
+            pendingCount += 1}
+          poll
+        }).await.unwrap( );
+      }
+
+      // Through the Sink implementation as well.
+      SinkExt::send(&mut sender, 100).await.unwrap( );
+    });
+    drop(sender);
+
+    assert!(pendingCount > 0);
+    assert_eq!((0..=100).collect::<Vec<_>>( ), consumer.join( ).unwrap( ));
+  }
+
+  #[cfg(feature = "async")]
+  #[test]
+  fn case_DroppingReceiverWakesAsyncSender( ) {
+    use futures::SinkExt;
+
+    let (mut sender, receiver)= Channel::bounded(1);
+    sender.send(0).unwrap( );
+
+    let handle= std::thread::spawn(move || {
+      std::thread::sleep(std::time::Duration::from_millis(50));
+      drop(receiver);
+    });
+
+    let result= futures::executor::block_on(SinkExt::send(&mut sender, 1));
+    assert_eq!(Err(SendError(1)), result);
+
+    handle.join( ).unwrap( );
+  }
+
+  #[test]
+  fn case_Stats( ) {
+    let (sender, mut receiver)= Channel::new( );
+
+    sender.send_all(0..10).unwrap( );
+    for _ in 0..4 {
+      receiver.receive( );}
+
+    let stats= receiver.stats( );
+    assert_eq!(10, stats.sent);
+    assert_eq!(4, stats.received);
+    assert!(stats.depthHighwater >= 10 - 4);
+
+    let clonedSender= sender.clone( );
+    assert_eq!(2, clonedSender.stats( ).senderCount);
+    assert_eq!(1, sender.stats( ).receiverCount);
+
+    drop(clonedSender);
+    assert_eq!(1, receiver.stats( ).senderCount);
+  }
+
+  #[test]
+  fn case_CacheBatchBoundsLateMessageLatency( ) {
+    // Returns how many items the receiver hands out from its cache, without looking at the shared
+    // queue, after a late message gets sent.
+    fn cachedAheadOfLateMessage(sender: Sender<usize>, mut receiver: Receiver<usize>) -> usize {
+      sender.send_all(0..10_000).unwrap( );
+      assert_eq!(Some(0), receiver.receive( ));
+
+      sender.send(usize::MAX).unwrap( );
+      receiver.cache.len( )
+    }
+
+    let (sender, receiver)= Channel::new( );
+    assert_eq!(9_999, cachedAheadOfLateMessage(sender, receiver));
+
+    let (sender, receiver)= Channel::with_cache_batch(16);
+    assert_eq!(16, cachedAheadOfLateMessage(sender, receiver));
+
+    // Ordering is unaffected by the cache batch size.
+    let (sender, mut receiver)= Channel::with_cache_batch(3);
+    sender.send_all(0..10).unwrap( );
+    drop(sender);
+
+    let mut received= Vec::new( );
+    while let Some(item)= receiver.receive( ) {
+      received.push(item);
+      assert!(receiver.cache.len( ) <= 3);
+    }
+    assert_eq!((0..10).collect::<Vec<_>>( ), received);
+
+    // Senders observe the items which aren't cached yet.
+    let (sender, mut receiver)= Channel::with_cache_batch(0);
+    sender.send_all(0..10).unwrap( );
+    receiver.receive( );
+    assert_eq!(9, sender.channel.queue.lock( ).unwrap( )._queue.len( ));
+  }
+
+  #[test]
+  fn case_PriorityMajorFifoMinor( ) {
+    let (sender, mut receiver)= Channel::priority( );
+
+    let handles: Vec<_>= ["A", "B"].into_iter( ).map(|thread| {
+      let sender= sender.clone( );
+
+      std::thread::spawn(move || {
+        for i in 0..30 {
+          sender.send_with_priority((i % 3) as u8, (thread, i)).unwrap( );}
+      })
+    }).collect( );
+    drop(sender);
+
+    for handle in handles {
+      handle.join( ).unwrap( );}
+
+    let mut received= Vec::new( );
+    while let Some(item)= receiver.receive( ) {
+      received.push(item);}
+
+    assert_eq!(60, received.len( ));
+
+    // Priority major.
+    let priorities: Vec<_>= received.iter( ).map(|(_, i)| i % 3).collect( );
+    assert!(priorities.windows(2).all(|window| window[0] >= window[1]));
+
+    // FIFO minor : within a priority, each thread's messages arrive in the order they were sent.
+    for thread in ["A", "B"] {
+      let sequence: Vec<_>= received.iter( )
+        .filter(|(t, _)| *t == thread)
+        .map(|(_, i)| *i)
+        .collect( );
+      let mut expected: Vec<_>= (0..30).collect( );
+      expected.sort_by_key(|i| std::cmp::Reverse(i % 3));
+
+      assert_eq!(expected, sequence);
+    }
+  }
+
+  #[test]
+  fn case_PriorityTiesPreserveFifo( ) {
+    let (sender, mut receiver)= Channel::priority( );
+
+    sender.send("low-1").unwrap( );
+    sender.send_with_priority(5, "high-1").unwrap( );
+    sender.send("low-2").unwrap( );
+    sender.send_with_priority(5, "high-2").unwrap( );
+    drop(sender);
+
+    assert_eq!(Some("high-1"), receiver.receive( ));
+    assert_eq!(Some("high-2"), receiver.receive( ));
+    assert_eq!(Some("low-1"), receiver.receive( ));
+    assert_eq!(Some("low-2"), receiver.receive( ));
+    assert_eq!(None, receiver.receive( ));
+  }
+
+  #[test]
+  fn case_DrainRemaining( ) {
+    let (sender, mut receiver)= Channel::new( );
+
+    sender.send_all(0..5).unwrap( );
+    assert_eq!(Some(0), receiver.receive( )); // Moves 1..5 into the cache.
+    sender.send_all(5..8).unwrap( );
+
+    assert_eq!((1..8).collect::<Vec<_>>( ), receiver.drain_remaining( ));
+
+    // The sender is still alive, but the channel is closed now.
+    assert_eq!(Err(SendError(8)), sender.send(8));
+  }
+
+  #[test]
+  fn case_DroppingReceiverDropsQueuedItemsInOrder( ) {
+    struct Recorder(usize, Arc<Mutex<Vec<usize>>>);
+
+    impl Drop for Recorder {
+      fn drop(&mut self) {
+        self.1.lock( ).unwrap( ).push(self.0)}
+    }
+
+    let dropped= Arc::new(Mutex::new(Vec::new( )));
+    let (sender, mut receiver)= Channel::new( );
+
+    sender.send_all((0..5).map(|i| Recorder(i, dropped.clone( )))).unwrap( );
+    drop(receiver.receive( )); // Moves 1..5 into the cache.
+    sender.send_all((5..8).map(|i| Recorder(i, dropped.clone( )))).unwrap( );
+
+    drop(receiver);
+    assert_eq!((0..8).collect::<Vec<_>>( ), *dropped.lock( ).unwrap( ));
+
+    // Nothing is left behind in the shared queue, even though the sender is still alive.
+    assert!(sender.channel.queue.lock( ).unwrap( )._queue.is_empty( ));
+  }
+
+  // Passes sequenced integers from one thread to another, asserting that nothing is lost,
+  // duplicated or reordered.
+  fn spscStress(count: usize, capacity: usize) {
+    let (mut sender, mut receiver)= spsc::channel(capacity);
+
+    let producer= std::thread::spawn(move || {
+      for i in 0..count {
+        sender.send(i).unwrap( );}
+    });
+
+    let mut expected= 0;
+    while let Some(i)= receiver.receive( ) {
+      assert_eq!(expected, i);
+      expected += 1;
+    }
+    assert_eq!(count, expected);
+
+    producer.join( ).unwrap( );
+  }
+
+  #[test]
+  #[cfg_attr(miri, ignore)]
+  fn case_SpscMillionSequencedIntegers( ) {
+    spscStress(1_000_000, 1024);
+  }
+
+  #[test]
+  fn case_SpscSmallStress( ) {
+    spscStress(1_000, 4);
+  }
+
+  #[test]
+  fn case_SpscTryOperations( ) {
+    let (mut sender, mut receiver)= spsc::channel(3); // Rounded up to 4.
+
+    assert_eq!(Err(TryRecvError::Empty), receiver.try_recv( ));
+
+    for i in 0..4 {
+      sender.try_send(i).unwrap( );}
+    assert_eq!(Err(TrySendError::Full(4)), sender.try_send(4));
+
+    assert_eq!(Ok(0), receiver.try_recv( ));
+    sender.try_send(4).unwrap( );
+
+    drop(sender);
+    assert_eq!(vec!{ 1, 2, 3, 4 }, std::iter::from_fn(|| receiver.receive( )).collect::<Vec<_>>( ));
+    assert_eq!(Err(TryRecvError::Disconnected), receiver.try_recv( ));
+
+    let (mut sender, receiver)= spsc::channel(1);
+    drop(receiver);
+    assert_eq!(Err(TrySendError::Disconnected(1)), sender.try_send(1));
+  }
+
+  #[test]
+  fn case_SpscDropsUnreceivedItems( ) {
+    let item= Arc::new(( ));
+
+    let (mut sender, receiver)= spsc::channel(4);
+    sender.try_send(item.clone( )).unwrap( );
+    sender.try_send(item.clone( )).unwrap( );
+
+    drop(sender);
+    drop(receiver);
+    assert_eq!(1, Arc::strong_count(&item));
+  }
+
+  #[test]
+  fn case_DisconnectionProbes( ) {
+    let (sender, mut receiver)= Channel::new( );
+    let clonedSender= sender.clone( );
+
+    assert!(sender.same_channel(&clonedSender));
+    assert!(!sender.same_channel(&Channel::new( ).0));
+
+    sender.send(1).unwrap( );
+    drop(sender);
+    drop(clonedSender);
+
+    // All the senders are gone, but there's still something to receive.
+    assert!(!receiver.is_disconnected( ));
+    assert_eq!(Some(1), receiver.receive( ));
+    assert!(receiver.is_disconnected( ));
+
+    let (sender, receiver)= Channel::<( )>::bounded(1);
+    assert!(!sender.is_disconnected( ));
+
+    drop(receiver);
+    assert!(sender.is_disconnected( ));
+  }
+
+  #[test]
+  fn case_TrySendAndSendTimeout( ) {
+    let (sender, mut receiver)= Channel::bounded(2);
+
+    sender.try_send(0).unwrap( );
+    sender.try_send(1).unwrap( );
+    assert_eq!(Err(TrySendError::Full(2)), sender.try_send(2));
+
+    let timeout= std::time::Duration::from_millis(20);
+    assert_eq!(Err(SendTimeoutError::Timeout(2)), sender.send_timeout(2, timeout));
+
+    let consumer= std::thread::spawn(move || {
+      std::thread::sleep(std::time::Duration::from_millis(20));
+      assert_eq!(Some(0), receiver.receive( ));
+      receiver
+    });
+
+    let startedAt= std::time::Instant::now( );
+    let deadline= std::time::Duration::from_secs(5);
+    assert_eq!(Ok(( )), sender.send_timeout(2, deadline));
+    assert!(startedAt.elapsed( ) < deadline);
+
+    drop(consumer.join( ).unwrap( ));
+    assert_eq!(Err(TrySendError::Disconnected(3)), sender.try_send(3));
+    assert_eq!(Err(SendTimeoutError::Disconnected(3)), sender.send_timeout(3, timeout));
+  }
+
+  #[test]
+  fn case_MappedReceiver( ) {
+    let (sender, receiver)= Channel::new( );
+    let mut receiver= receiver.map(|s: String| s.len( ));
+
+    sender.send("a".to_owned( )).unwrap( );
+    sender.send("abc".to_owned( )).unwrap( );
+    assert_eq!(2, receiver.len( ));
+
+    assert_eq!(Ok(1), receiver.try_recv( ));
+    assert_eq!(Some(3), receiver.receive( ));
+    assert_eq!(Err(TryRecvError::Empty), receiver.try_recv( ));
+
+    sender.send("ab".to_owned( )).unwrap( );
+    drop(sender);
+
+    assert_eq!(vec!{ 2 }, receiver.iter( ).collect::<Vec<_>>( ));
+    assert_eq!(None, receiver.receive( ));
+    assert!(receiver.is_disconnected( ));
+  }
+
+  #[test]
+  fn case_FilteredReceiver( ) {
+    let (sender, receiver)= Channel::new( );
+    let mut receiver= receiver.filter(|i: &i32| i % 2 == 0);
+
+    sender.send_all(0..10).unwrap( );
+    assert_eq!(10, receiver.len( )); // Just an upper bound.
+
+    assert_eq!(Ok(0), receiver.try_recv( ));
+    assert_eq!(Some(2), receiver.receive( ));
+
+    sender.send(11).unwrap( );
+    drop(sender);
+
+    assert_eq!(vec!{ 4, 6, 8 }, receiver.iter( ).collect::<Vec<_>>( ));
+    assert_eq!(Err(TryRecvError::Disconnected), receiver.try_recv( ));
+  }
+
+  #[test]
+  fn case_WithCapacityAvoidsReallocation( ) {
+    let initial= 64;
+    let (sender, mut receiver)= Channel::with_capacity(initial);
+
+    let capacity= receiver.queue_capacity( );
+    assert!(capacity >= initial);
+
+    sender.send_all(0..initial).unwrap( );
+    assert_eq!(capacity, receiver.queue_capacity( ));
+
+    // The swap hands the preallocated cache buffer over to the shared queue.
+    assert_eq!(Some(0), receiver.receive( ));
+    assert!(receiver.queue_capacity( ) >= initial);
+
+    while receiver.try_recv( ).is_ok( ) { }
+
+    let capacity= receiver.queue_capacity( );
+    sender.send_all(0..initial).unwrap( );
+    assert_eq!(capacity, receiver.queue_capacity( ));
+  }
+
+  #[test]
+  fn case_BroadcastEveryReceiverSeesEveryMessage( ) {
+    use broadcast::BroadcastRecvError;
+
+    let (sender, mut firstReceiver)= Channel::broadcast(16);
+    let mut secondReceiver= sender.subscribe( );
+
+    let handle= std::thread::spawn(move || {
+      (0..10).map(|_| secondReceiver.receive( ).unwrap( )).collect::<Vec<_>>( )
+    });
+
+    for i in 0..10 {
+      sender.send(i.to_string( )).unwrap( );}
+    drop(sender);
+
+    let expected: Vec<_>= (0..10).map(|i| i.to_string( )).collect( );
+    assert_eq!(expected, handle.join( ).unwrap( ));
+
+    let received: Vec<_>= std::iter::from_fn(|| firstReceiver.receive( ).ok( )).collect( );
+    assert_eq!(expected, received);
+    assert_eq!(Err(BroadcastRecvError::Disconnected), firstReceiver.receive( ));
+  }
+
+  #[test]
+  fn case_BroadcastSlowReceiverLags( ) {
+    use broadcast::BroadcastRecvError;
+
+    let (sender, mut slowReceiver)= Channel::broadcast(4);
+
+    for i in 0..10 {
+      sender.send(i).unwrap( );}
+
+    // Messages 0..6 were overwritten. It resumes from the oldest retained message.
+    assert_eq!(Err(BroadcastRecvError::Lagged(6)), slowReceiver.receive( ));
+    assert_eq!(Ok(6), slowReceiver.receive( ));
+
+    // A new subscriber only sees what's sent from now on.
+    let mut newReceiver= sender.subscribe( );
+    sender.send(10).unwrap( );
+
+    assert_eq!(Ok(10), newReceiver.receive( ));
+    let received: Vec<_>= (0..4).map(|_| slowReceiver.receive( ).unwrap( )).collect( );
+    assert_eq!(vec!{ 7, 8, 9, 10 }, received);
+
+    drop(slowReceiver);
+    drop(newReceiver);
+    assert_eq!(Err(SendError(11)), sender.send(11));
+  }
+
+  // Nothing forces T to be 'static. So, items borrowed from the stack can be sent across scoped
+  // threads.
+  #[test]
+  fn case_ScopedThreadsWithBorrowedItems( ) {
+    use crate::prelude::*;
+
+    let words= String::from("the quick brown fox");
+    let words= words.as_str( );
+
+    let (sender, mut receiver)= channel::<&str>( );
+    let (syncSender, mut syncReceiver)= bounded::<&str>(1);
+
+    std::thread::scope(|scope| {
+      scope.spawn(move || {
+        for word in words.split(' ') {
+          sender.send(word).unwrap( );}
+      });
+
+      scope.spawn(move || {
+        syncSender.send(&words[..3]).unwrap( );
+        syncSender.send(&words[4..9]).unwrap( );
+      });
+
+      assert_eq!(vec!{ "the", "quick", "brown", "fox" }, receiver.iter( ).collect::<Vec<_>>( ));
+      assert_eq!(vec!{ "the", "quick" }, syncReceiver.iter( ).collect::<Vec<_>>( ));
+    });
+  }
+
+  // A tiny xorshift PRNG, for the stress tests. Keeps the test runs deterministic.
+  fn xorshift(state: &mut u64) -> u64 {
+    *state ^= *state << 13;
+    *state ^= *state >> 7;
+    *state ^= *state << 17;
+    *state
+  }
+
+  // Randomized interleavings of send / sender drop / receive across threads, with small sleeps in
+  // between. A lost notification shows up as a hang (the receiver sleeps forever), and a wrong
+  // predicate as lost items.
+  #[test]
+  #[cfg_attr(miri, ignore)]
+  fn case_NotifyLossStress( ) {
+    let mut seed= 0x2545F4914F6CDD1D;
+
+    for _ in 0..2_000 {
+      let senderCount= 1 + (xorshift(&mut seed) % 3) as usize;
+      let itemsPerSender= (xorshift(&mut seed) % 8) as usize;
+      let cacheBatch= [0, 1, 3, usize::MAX][(xorshift(&mut seed) % 4) as usize];
+      let refillThreshold= (xorshift(&mut seed) % 3) as usize;
+
+      let (sender, mut receiver)= Channel::with_cache_refill(cacheBatch, refillThreshold);
+
+      let handles: Vec<_>= (0..senderCount).map(|_| {
+        let sender= sender.clone( );
+        let mut seed= xorshift(&mut seed);
+
+        std::thread::spawn(move || {
+          for i in 0..itemsPerSender {
+            if xorshift(&mut seed).is_multiple_of(4) {
+              std::thread::sleep(std::time::Duration::from_micros(xorshift(&mut seed) % 50))}
+
+            sender.send(i).unwrap( );
+          }
+        })
+      }).collect( );
+      drop(sender);
+
+      let mut received= 0;
+      while receiver.receive( ).is_some( ) {
+        received += 1;
+
+        if xorshift(&mut seed).is_multiple_of(8) {
+          std::thread::yield_now( )}
+      }
+      assert_eq!(senderCount * itemsPerSender, received);
+
+      for handle in handles {
+        handle.join( ).unwrap( );}
+    }
+  }
+
+  #[test]
+  #[cfg_attr(miri, ignore)]
+  fn case_BoundedNotifyLossStress( ) {
+    let mut seed= 0x9E3779B97F4A7C15;
+
+    for _ in 0..1_000 {
+      let capacity= 1 + (xorshift(&mut seed) % 2) as usize;
+      let (sender, mut receiver)= Channel::bounded(capacity);
+
+      let handles: Vec<_>= (0..2).map(|_| {
+        let sender= sender.clone( );
+        let mut seed= xorshift(&mut seed);
+
+        std::thread::spawn(move || {
+          for i in 0..5 {
+            if xorshift(&mut seed).is_multiple_of(3) {
+              std::thread::sleep(std::time::Duration::from_micros(xorshift(&mut seed) % 30))}
+
+            sender.send(i).unwrap( );
+          }
+        })
+      }).collect( );
+      drop(sender);
+
+      assert_eq!(10, receiver.iter( ).count( ));
+
+      for handle in handles {
+        handle.join( ).unwrap( );}
+    }
+  }
+
+  #[test]
+  fn case_BackpressureHook( ) {
+    let (sender, mut receiver)= Channel::bounded(1);
+
+    let events= std::sync::Arc::new(std::sync::Mutex::new(Vec::new( )));
+    sender.set_backpressure_hook({
+      let events= events.clone( );
+      move |event| events.lock( ).unwrap( ).push(event)
+    });
+
+    // Sending into a queue with space doesn't block.
+    sender.send(1).unwrap( );
+    assert!(events.lock( ).unwrap( ).is_empty( ));
+
+    let handle= std::thread::spawn(move || sender.send(2).unwrap( ));
+
+    // Wait till the sender has blocked.
+    while events.lock( ).unwrap( ).is_empty( ) {
+      std::thread::yield_now( )}
+    std::thread::sleep(std::time::Duration::from_millis(10));
+
+    assert_eq!(Some(1), receiver.receive( ));
+    handle.join( ).unwrap( );
+    assert_eq!(Some(2), receiver.receive( ));
+
+    let events= events.lock( ).unwrap( );
+    assert_eq!(2, events.len( ));
+    assert_eq!(BackpressureEvent::Blocked { queueLength: 1 }, events[0]);
+
+    let BackpressureEvent::Unblocked { queueLength, waited }= events[1] else {
+      panic!("expected an Unblocked event, got {:?}", events[1])};
+    assert_eq!(0, queueLength);
+    assert!(waited >= std::time::Duration::from_millis(10));
+  }
+
+  #[test]
+  fn case_BackpressureHookOnTimeout( ) {
+    let (sender, receiver)= Channel::bounded(1);
+
+    let events= std::sync::Arc::new(std::sync::Mutex::new(Vec::new( )));
+    sender.set_backpressure_hook({
+      let events= events.clone( );
+      move |event| events.lock( ).unwrap( ).push(event)
+    });
+
+    sender.send(1).unwrap( );
+    assert_eq!(
+      Err(SendTimeoutError::Timeout(2)),
+      sender.send_timeout(2, std::time::Duration::from_millis(5))
+    );
+
+    let events= events.lock( ).unwrap( );
+    assert!(matches!(
+      events[..],
+      [
+        BackpressureEvent::Blocked { queueLength: 1 },
+        BackpressureEvent::Unblocked { queueLength: 1, .. }
+      ]
+    ));
+  }
+
+  // Three producers block on a full channel, one after the other. Then a slow consumer drains it.
+  // Returns the order in which the items were received.
+  fn blockedProducers(sender: SyncSender<usize>, mut receiver: Receiver<usize>) -> Vec<usize> {
+    sender.send(0).unwrap( );
+
+    let handles: Vec<_>= (1..=3).map(|i| {
+      let handle= std::thread::spawn({
+        let sender= sender.clone( );
+        move || sender.send(i).unwrap( )
+      });
+
+      while sender.waiting_senders( ) < i {
+        std::thread::yield_now( )}
+
+      handle
+    }).collect( );
+    drop(sender);
+
+    let received= std::iter::from_fn(|| {
+      std::thread::sleep(std::time::Duration::from_millis(5));
+      receiver.receive( )
+    }).collect( );
+
+    for handle in handles {
+      handle.join( ).unwrap( );}
+
+    received
+  }
+
+  #[test]
+  fn case_FairBoundedSendsInTicketOrder( ) {
+    for _ in 0..20 {
+      let (sender, receiver)= Channel::bounded_fair(1);
+      assert_eq!(vec!{ 0, 1, 2, 3 }, blockedProducers(sender, receiver));
+    }
+
+    // Once somebody's waiting in line, try_send can't jump ahead.
+    let (sender, mut receiver)= Channel::bounded_fair(1);
+    sender.send(0).unwrap( );
+
+    let handle= std::thread::spawn({
+      let sender= sender.clone( );
+      move || sender.send(1).unwrap( )
+    });
+    while sender.waiting_senders( ) < 1 {
+      std::thread::yield_now( )}
+
+    assert_eq!(Some(0), receiver.receive( ));
+    handle.join( ).unwrap( );
+    assert_eq!(Err(TrySendError::Full(2)), sender.try_send(2));
+
+    // The line is empty after a timed out sender gives up.
+    assert_eq!(
+      Err(SendTimeoutError::Timeout(2)),
+      sender.send_timeout(2, std::time::Duration::from_millis(5))
+    );
+    assert_eq!(0, sender.waiting_senders( ));
+
+    assert_eq!(Some(1), receiver.receive( ));
+    assert_eq!(Ok(( )), sender.try_send(2));
+  }
+
+  #[test]
+  fn case_UnfairBoundedDeliversEverything( ) {
+    let (sender, receiver)= Channel::bounded(1);
+
+    let mut received= blockedProducers(sender, receiver);
+    assert_eq!(0, received[0]);
+
+    received.sort( );
+    assert_eq!(vec!{ 0, 1, 2, 3 }, received);
+  }
+
+  #[test]
+  fn case_RecvRef( ) {
+    let (sender, mut receiver)= Channel::new( );
+    sender.send_all(vec!{ String::from("a"), String::from("b") }).unwrap( );
+
+    // Peek and keep.
+    let guard= receiver.recv_ref( ).unwrap( );
+    assert_eq!("a", guard.as_str( ));
+    guard.keep( );
+    assert_eq!(Some(String::from("a")), receiver.receive( ));
+
+    // Take.
+    let guard= receiver.recv_ref( ).unwrap( );
+    assert_eq!("b", *guard);
+    assert_eq!("b", guard.take( ));
+
+    // Interleaved with new sends.
+    sender.send(String::from("c")).unwrap( );
+    drop(receiver.recv_ref( )); // Dropping keeps the item too.
+    sender.send(String::from("d")).unwrap( );
+    receiver.recv_ref( ).unwrap( ).keep( );
+    sender.send(String::from("e")).unwrap( );
+
+    drop(sender);
+    assert_eq!(vec!{ "c", "d", "e" }, receiver.iter( ).collect::<Vec<_>>( ));
+    assert!(receiver.recv_ref( ).is_none( ));
+
+    // Kept items aren't accounted for as received.
+    assert_eq!(5, receiver.stats( ).received);
+
+    // Same with a bounded channel (which doesn't make use of the cache).
+    let (sender, mut receiver)= Channel::bounded(1);
+    sender.send(1).unwrap( );
+    receiver.recv_ref( ).unwrap( ).keep( );
+    sender.send(2).unwrap( );
+
+    assert_eq!(Some(1), receiver.receive( ));
+    assert_eq!(Some(2), receiver.receive( ));
+    assert_eq!(2, receiver.stats( ).received);
+  }
+
+  #[cfg(feature = "serde")]
+  #[test]
+  fn case_Snapshot( ) {
+    let (sender, mut receiver)= Channel::new( );
+
+    sender.send_all(0..4).unwrap( );
+    assert_eq!(Some(0), receiver.receive( )); // Moves 1..4 into the cache.
+    sender.send_all(4..6).unwrap( );
+    assert_eq!(2, sender.queued_len( ));
+
+    let snapshot= receiver.snapshot( );
+    assert_eq!(vec!{ 1, 2, 3, 4, 5 }, snapshot);
+
+    drop(sender);
+    assert_eq!(snapshot, receiver.iter( ).collect::<Vec<_>>( ));
+  }
+
+  #[cfg(feature = "serde")]
+  #[test]
+  fn case_SnapshotDoesntDisturbBlockedSenders( ) {
+    let (sender, mut receiver)= Channel::bounded(2);
+    sender.send(String::from("a")).unwrap( );
+    sender.send(String::from("b")).unwrap( );
+
+    let handle= std::thread::spawn({
+      let sender= sender.clone( );
+      move || sender.send(String::from("c")).unwrap( )
+    });
+    while sender.waiting_senders( ) < 1 {
+      std::thread::yield_now( )}
+
+    let mut json= Vec::new( );
+    receiver.serialize_snapshot(&mut serde_json::Serializer::new(&mut json)).unwrap( );
+    assert_eq!(vec!{ "a", "b" }, serde_json::from_slice::<Vec<String>>(&json).unwrap( ));
+
+    // The sender is still blocked.
+    std::thread::sleep(std::time::Duration::from_millis(10));
+    assert_eq!(1, sender.waiting_senders( ));
+    assert_eq!(2, sender.queued_len( ));
+
+    drop(sender);
+    assert_eq!(Some(String::from("a")), receiver.receive( ));
+    handle.join( ).unwrap( );
+    assert_eq!(vec!{ "b", "c" }, receiver.iter( ).collect::<Vec<_>>( ));
+  }
+
+  #[test]
+  fn case_PanicGuardPoisons( ) {
+    let (sender, mut receiver)= Channel::new( );
+
+    let handle= std::thread::spawn(move || {
+      let _guard= sender.panic_guard( );
+
+      sender.send("header").unwrap( );
+      sender.send("part 1").unwrap( );
+      panic!("dying before sending part 2");
+    });
+    assert!(handle.join( ).is_err( ));
+
+    assert_eq!(Ok("header"), receiver.recv( ));
+    assert_eq!(Ok("part 1"), receiver.recv( ));
+    assert_eq!(Err(RecvError::Poisoned("a sender panicked")), receiver.recv( ));
+    assert_eq!(Err(TryRecvError::Poisoned("a sender panicked")), receiver.try_recv( ));
+
+    // The guard does nothing, unless the thread is panicking.
+    let (sender, mut receiver)= Channel::new( );
+    drop(sender.panic_guard( ));
+    sender.send(1).unwrap( );
+    drop(sender);
+    assert_eq!(Ok(1), receiver.recv( ));
+    assert_eq!(Err(RecvError::Disconnected), receiver.recv( ));
+  }
+
+  #[test]
+  fn case_Poison( ) {
+    let (sender, mut receiver)= Channel::bounded(1);
+    sender.send(1).unwrap( );
+
+    // Wakes up the blocked senders as well.
+    let handle= std::thread::spawn({
+      let sender= sender.clone( );
+      move || sender.send(2)
+    });
+    while sender.waiting_senders( ) < 1 {
+      std::thread::yield_now( )}
+
+    sender.poison("bad input");
+    sender.poison("ignored");
+    assert_eq!(Err(SendError(2)), handle.join( ).unwrap( ));
+    assert_eq!(Err(SendError(3)), sender.send(3));
+
+    let mut iter= receiver.iter( );
+    assert_eq!(vec!{ 1 }, iter.by_ref( ).collect::<Vec<_>>( ));
+    assert_eq!(Some(RecvError::Poisoned("bad input")), iter.error( ));
+  }
+
+  #[test]
+  fn case_CloseWakesBlockedReceiver( ) {
+    let (sender, mut receiver)= Channel::<( )>::new( );
+
+    let handle= std::thread::spawn(move || receiver.receive( ));
+
+    std::thread::sleep(std::time::Duration::from_millis(50));
+    sender.close( );
+
+    assert_eq!(None, handle.join( ).unwrap( ));
+  }
+
+  #[test]
+  fn case_RecvErrorTellsCloseFromDisconnect( ) {
+    // Closed, while the sender is still alive.
+    let (sender, mut receiver)= Channel::new( );
+    sender.send(1).unwrap( );
+    receiver.close( );
+
+    let mut iter= receiver.iter( );
+    assert_eq!(None, iter.error( ));
+    assert_eq!(vec!{ 1 }, iter.by_ref( ).collect::<Vec<_>>( ));
+    assert_eq!(Some(RecvError::Closed), iter.error( ));
+
+    // Closing wins, even if all the senders are gone as well.
+    drop(sender);
+    assert_eq!(Err(RecvError::Closed), receiver.recv( ));
+
+    // All the senders are gone, without closing.
+    let (sender, mut receiver)= Channel::new( );
+    sender.send(1).unwrap( );
+
+    let mut tryIter= receiver.try_iter( );
+    assert_eq!(Some(1), tryIter.next( ));
+    assert_eq!(None, tryIter.next( ));
+    assert_eq!(None, tryIter.error( )); // Just empty for now.
+
+    drop(sender);
+    assert_eq!(None, tryIter.next( ));
+    assert_eq!(Some(RecvError::Disconnected), tryIter.error( ));
+
+    assert_eq!(Err(TryRecvError::Disconnected), receiver.try_recv( ));
+    assert_eq!(Err(RecvError::Disconnected), receiver.recv( ));
+  }
+
+  #[test]
+  fn case_CacheRefillThreshold( ) {
+    // Receives 3 items out of 10, and returns what the sender then observes : the number of items
+    // in the shared queue, and the number of items received.
+    fn observedBySender(sender: &Sender<usize>, receiver: &mut Receiver<usize>) -> (usize, u64) {
+      sender.send_all(0..10).unwrap( );
+      for expected in 0..3 {
+        assert_eq!(Some(expected), receiver.receive( ));}
+
+      (sender.queued_len( ), sender.stats( ).received)
+    }
+
+    // The cache (holding 1 to 4) drains till it's empty, before the receiver looks at the shared
+    // queue again.
+    let (sender, mut receiver)= Channel::with_cache_batch(4);
+    assert_eq!((5, 1), observedBySender(&sender, &mut receiver));
+
+    // Once the cache drains down to 2 items (3 and 4), it's topped up with 5 and 6.
+    let (sender, mut receiver)= Channel::with_cache_refill(4, 2);
+    assert_eq!((3, 3), observedBySender(&sender, &mut receiver));
+    assert_eq!(vec!{ 3, 4, 5, 6 }, Vec::from(receiver.cache.clone( )));
+
+    // Ordering is unaffected.
+    drop(sender);
+    assert_eq!((3..10).collect::<Vec<_>>( ), receiver.iter( ).collect::<Vec<_>>( ));
+  }
+}
+
+
+// Run with : RUSTFLAGS="--cfg loom" cargo test --release loom
+#[cfg(all(test, loom))]
+mod loomTests {
+  use super::*;
+  use loom::thread;
+
+  #[test]
+  fn loom_SingleSendReceive( ) {
+    loom::model(|| {
+      let (sender, mut receiver)= Channel::new( );
+
+      let handle= thread::spawn(move || sender.send(42).unwrap( ));
+
+      assert_eq!(Some(42), receiver.receive( ));
+      assert_eq!(None, receiver.receive( ));
+
+      handle.join( ).unwrap( );
+    });
+  }
+
+  #[test]
+  fn loom_LastSenderDropRace( ) {
+    loom::model(|| {
+      let (sender, mut receiver)= Channel::new( );
+      let clonedSender= sender.clone( );
+
+      let sendingHandle= thread::spawn(move || sender.send(1).unwrap( ));
+      let droppingHandle= thread::spawn(move || drop(clonedSender));
+
+      // The receiver must neither miss the item, nor sleep forever after the last sender is gone.
+      assert_eq!(Some(1), receiver.receive( ));
+      assert_eq!(None, receiver.receive( ));
+
+      sendingHandle.join( ).unwrap( );
+      droppingHandle.join( ).unwrap( );
+    });
+  }
+
+  #[test]
+  fn loom_TwoSendersRacingNotify( ) {
+    loom::model(|| {
+      let (sender, mut receiver)= Channel::new( );
+      let clonedSender= sender.clone( );
+
+      let handles= [
+        thread::spawn(move || sender.send(1).unwrap( )),
+        thread::spawn(move || clonedSender.send(2).unwrap( ))
+      ];
+
+      let mut received= vec!{ receiver.receive( ).unwrap( ), receiver.receive( ).unwrap( ) };
+      received.sort( );
+      assert_eq!(vec!{ 1, 2 }, received);
+      assert_eq!(None, receiver.receive( ));
+
+      for handle in handles {
+        handle.join( ).unwrap( );}
+    });
+  }
+
+  #[test]
+  fn loom_ReceiverDropRace( ) {
+    loom::model(|| {
+      let (sender, receiver)= Channel::bounded(1);
+
+      let handle= thread::spawn(move || {
+        // Either succeeds, or fails because the receiver is gone. It must never block forever.
+        let _= sender.send(1);
+        let _= sender.send(2);
+      });
+
+      drop(receiver);
+      handle.join( ).unwrap( );
+    });
+  }
+
+  #[test]
+  fn loom_SpscParkUnparkRace( ) {
+    // Exploring every interleaving takes minutes. Bounding the preemptions (as loom suggests) still
+    // covers the races between a park and an unpark.
+    let mut model= loom::model::Builder::new( );
+    model.preemption_bound= Some(3);
+
+    model.check(|| {
+      let (mut sender, mut receiver)= spsc::channel(1);
+
+      // The second send finds the ring full and parks, while the receiver may be parked waiting for
+      // the first item. Neither side may miss the other's wake up.
+      let handle= thread::spawn(move || {
+        sender.send(1).unwrap( );
+        sender.send(2).unwrap( );
+      });
+
+      assert_eq!(Some(1), receiver.receive( ));
+      assert_eq!(Some(2), receiver.receive( ));
+      assert_eq!(None, receiver.receive( ));
+
+      handle.join( ).unwrap( );
+    });
+  }
+
+  #[test]
+  fn loom_BroadcastWakesEveryReceiver( ) {
+    loom::model(|| {
+      let (sender, mut receiver)= Channel::broadcast(2);
+      let mut otherReceiver= sender.subscribe( );
+
+      let handle= thread::spawn(move || {
+        assert_eq!(Ok(1), otherReceiver.receive( ));
+        assert_eq!(Err(broadcast::BroadcastRecvError::Disconnected), otherReceiver.receive( ));
+      });
+
+      sender.send(1).unwrap( );
+      drop(sender);
+
+      assert_eq!(Ok(1), receiver.receive( ));
+      assert_eq!(Err(broadcast::BroadcastRecvError::Disconnected), receiver.receive( ));
+
+      handle.join( ).unwrap( );
+    });
+  }
+}
+
+/*
+  Channel flavours -
+  |
+  |- 1. Asynchronous (Unbounded) channels
+  |
+  |- 2. Synchronous (bounded) channels - The queue in this case is of constant size. When the queue
+  |     fills up, all senders remain blocked until there is again some space available in the queue.
+  |
+  |- 3. Rendezvous channels
+  |
+  |- 4. Oneshot channels.
+*/
@@ -1,4 +1,9 @@
-#![allow(unused, non_snake_case)]
+#![allow(unused, non_snake_case, clippy::while_let_on_iterator, clippy::useless_vec)]
+
+use std::{
+  cmp::Ordering, collections::{HashSet, VecDeque}, fmt, hash::Hash, iter::{Fuse, FusedIterator},
+  ops::AddAssign
+};
 
 fn main( ) {
   for x in vec![ 2, 3 ] { }
@@ -24,34 +29,278 @@ fn main( ) {
 
 //---
 
-pub fn flatten<I>(iterator: I) -> Flatten<I>
+// Method-call counterparts of the free functions (and home of the other adapters in this crate), so
+// that they can be chained. Those which would clash with the methods of std's Iterator trait are
+// named with an '_ext' suffix.
+pub trait IteratorExt: Iterator + Sized {
+  /// Method-call counterpart of [flatten].
+  ///
+  /// ```
+  /// use iterators::IteratorExt;
+  ///
+  /// assert_eq!(vec![ 1, 2, 2 ], (0..3).map(|i| vec![ i; i ]).flatten_ext( ).collect::<Vec<_>>( ));
+  /// ```
+  fn flatten_ext(self) -> Flatten<Self>
+    where Self::Item: IntoIterator
+  {
+    Flatten::new(self)
+  }
+
+  fn flat_map_ext<F, U>(self, f: F) -> FlatMap<Self, F, U>
+    where F: FnMut(Self::Item) -> U,
+          U: IntoIterator
+  {
+    FlatMap::new(self, f)
+  }
+
+  fn interleave<B>(self, other: B) -> Interleave<Self, B::IntoIter>
+    where B: IntoIterator<Item = Self::Item>
+  {
+    Interleave::new(self, other.into_iter( ))
+  }
+
+  // Panics if size is 0, just like slice's chunks.
+  fn chunks_ext(self, size: usize) -> Chunks<Self> {
+    Chunks::new(self, size)
+  }
+
+  // Panics if size is 0, just like slice's windows.
+  fn windows_ext(self, size: usize) -> WindowsIter<Self>
+    where Self::Item: Clone
+  {
+    WindowsIter::new(self, size)
+  }
+
+  fn zip_longest_ext<B>(self, other: B) -> ZipLongest<Self, B::IntoIter>
+    where B: IntoIterator
+  {
+    ZipLongest::new(self, other.into_iter( ))
+  }
+
+  fn intersperse_ext(self, separator: Self::Item) -> Intersperse<Self>
+    where Self::Item: Clone
+  {
+    Intersperse { state: IntersperseState::new(self), separator }
+  }
+
+  // For separators which aren't Clone. The closure is called only when another item follows.
+  fn intersperse_with_ext<F>(self, separator: F) -> IntersperseWith<Self, F>
+    where F: FnMut( ) -> Self::Item
+  {
+    IntersperseWith { state: IntersperseState::new(self), separator }
+  }
+
+  fn chunk_by_ext<K, F>(self, keyFn: F) -> ChunkBy<Self, K, F>
+    where K: PartialEq,
+          F: FnMut(&Self::Item) -> K
+  {
+    ChunkBy { iterator: self.fuse( ), keyFn, pending: None }
+  }
+
+  // Both self and other must already be sorted.
+  fn merge<B>(self, other: B) -> Merge<Self, B::IntoIter>
+    where Self::Item: Ord,
+          B: IntoIterator<Item = Self::Item>
+  {
+    MergeBy::new(self, other.into_iter( ), Ord::cmp)
+  }
+
+  // Both self and other must already be sorted, as per the comparator.
+  fn merge_by<B, F>(self, other: B, comparator: F) -> MergeBy<Self, B::IntoIter, F>
+    where B: IntoIterator<Item = Self::Item>,
+          F: FnMut(&Self::Item, &Self::Item) -> Ordering
+  {
+    MergeBy::new(self, other.into_iter( ), comparator)
+  }
+
+  fn multipeek_ext(self) -> MultiPeek<Self> {
+    MultiPeek { iterator: self.fuse( ), buffer: VecDeque::new( ), cursor: 0 }
+  }
+
+  fn take_while_inclusive_ext<P>(self, predicate: P) -> TakeWhileInclusive<Self, P>
+    where P: FnMut(&Self::Item) -> bool
+  {
+    TakeWhileInclusive { iterator: self, predicate, done: false }
+  }
+
+  // NOTE - Memory grows with the number of distinct items, since a clone of each one is kept
+  // around. Use unique_by_ext to keep (smaller) keys instead.
+  fn unique_ext(self) -> Unique<Self>
+    where Self::Item: Clone + Eq + Hash
+  {
+    Unique { iterator: self, seen: HashSet::new( ) }
+  }
+
+  // Items are considered duplicates if they have the same key. Only the keys are kept around (so,
+  // memory grows with the number of distinct keys).
+  fn unique_by_ext<K, F>(self, keyFn: F) -> UniqueBy<Self, K, F>
+    where K: Eq + Hash,
+          F: FnMut(&Self::Item) -> K
+  {
+    UniqueBy { iterator: self, seen: HashSet::new( ), keyFn }
+  }
+
+  // Yields the items at positions offset, offset + step, offset + 2 * step and so on. Panics if
+  // step is 0, just like step_by.
+  fn stride_ext(self, step: usize, offset: usize) -> Stride<Self> {
+    Stride::new(self, step, offset)
+  }
+
+  // The closure gets the iterator itself, and pulls as many items from it as it wants, for each
+  // item it yields.
+  fn batching_ext<B, F>(self, f: F) -> Batching<Self, F>
+    where F: FnMut(&mut Self) -> Option<B>
+  {
+    Batching { iterator: self, f }
+  }
+
+  // Same as calling flatten_ext twice, but with a single layer of state.
+  fn flatten_deep2(self) -> FlattenDeep2<Self>
+    where Self::Item: IntoIterator,
+          <Self::Item as IntoIterator>::Item: IntoIterator
+  {
+    FlattenDeep2::new(self)
+  }
+
+  // Repeats the whole sequence, times number of times.
+  fn cycle_n_ext(self, times: usize) -> CycleN<Self>
+    where Self: Clone
+  {
+    CycleN::new(self, times)
+  }
+
+  // Repeats the whole sequence endlessly (unless it's empty).
+  fn cycle_ext(self) -> Cycle<Self>
+    where Self: Clone
+  {
+    Cycle { original: self.clone( ), current: self }
+  }
+
+  // Like inspect, but f also gets the position of the item (in the original sequence).
+  fn inspect_indexed_ext<F>(self, f: F) -> InspectIndexed<Self, F>
+    where F: FnMut(usize, &Self::Item)
+  {
+    InspectIndexed { iterator: self, index: 0, f }
+  }
+
+  // Like enumerate, but the count starts from start, and is of type N.
+  // NOTE - TryFrom<usize> (and not From<usize>) is required, since u64 and the like don't implement
+  // From<usize>. Panics if a count doesn't fit in N.
+  fn enumerate_from_ext<N>(self, start: N) -> EnumerateFrom<Self, N>
+    where N: TryFrom<usize> + AddAssign + Clone
+  {
+    EnumerateFrom { iterator: self, next: start }
+  }
+
+  // Meant for iterators whose size_hint is exact : the Vec is allocated just once, using the lower
+  // bound. In debug builds, panics if the lower bound turns out to be wrong.
+  fn collect_vec_exact(self) -> Vec<Self::Item> {
+    let (lower, _)= self.size_hint( );
+
+    let mut items= Vec::with_capacity(lower);
+    for item in self {
+      items.push(item);
+    }
+
+    debug_assert_eq!(lower, items.len( ), "size_hint's lower bound isn't the actual length");
+    items
+  }
+
+  // Stops at the first error. The items after it are never pulled.
+  fn try_collect_vec<T, E>(self) -> Result<Vec<T>, E>
+    where Self: Iterator<Item = Result<T, E>>
+  {
+    let mut items= Vec::new( );
+    for item in self {
+      items.push(item?);
+    }
+    Ok(items)
+  }
+}
+
+impl<I> IteratorExt for I
+  where I: Iterator
+{ }
+
+// Accepts anything which can be iterated over (like a Vec of Vecs), not just iterators.
+pub fn flatten<I>(iterator: I) -> Flatten<I::IntoIter>
   where I: IntoIterator,
         I::Item: IntoIterator
 {
-  Flatten::new(iterator)
+  Flatten::new(iterator.into_iter( ))
 }
 
 pub struct Flatten<O>
-  where O: IntoIterator,
+  where O: Iterator,
         O::Item: IntoIterator
 {
-  outerIterator: O,
+  // Fused, so that once it's exhausted, we never poll it again. Otherwise a non-fused outer iterator
+  // could make Flatten yield items after having returned None.
+  outerIterator: Fuse<O>,
 
   frontInnerIterator: Option<<O::Item as IntoIterator>::IntoIter>,
   backInnerIterator: Option<<O::Item as IntoIterator>::IntoIter>
 }
 
 impl<O> Flatten<O>
-  where O: IntoIterator,
+  where O: Iterator,
         O::Item: IntoIterator
 {
   pub fn new(iterator: O) -> Self {
     Self {
-      outerIterator: iterator,
+      outerIterator: iterator.fuse( ),
       frontInnerIterator: None,
       backInnerIterator: None
     }
   }
+
+  // Skips the next n items from the front. Returns how many of them couldn't be skipped, because
+  // Flatten ran out of items.
+  // Inner iterators whose size_hint is exact are skipped as a whole (or via their nth), without
+  // going through each of their items. So, side effects (like that of an inspect) of those items
+  // won't happen.
+  fn advanceFrontBy(&mut self, mut n: usize) -> usize {
+    while n > 0 {
+      if let Some(ref mut innerIterator)= self.frontInnerIterator {
+        n= Self::advanceInnerBy(innerIterator, n);
+        if n == 0 {
+          return 0}
+
+        self.frontInnerIterator= None;
+      }
+
+      match self.outerIterator.next( ) {
+        Some(nextFrontIterator) => self.frontInnerIterator= Some(nextFrontIterator.into_iter( )),
+
+        // The partially consumed back inner iterator is all that's left.
+        None => {
+          let Some(ref mut innerIterator)= self.backInnerIterator else { return n };
+
+          n= Self::advanceInnerBy(innerIterator, n);
+          if n > 0 {
+            self.backInnerIterator= None;
+          }
+          return n;
+        }
+      }
+    }
+    0
+  }
+
+  // Returns how many of the n items couldn't be skipped. If that's non-zero, then the inner
+  // iterator is exhausted.
+  fn advanceInnerBy(innerIterator: &mut <O::Item as IntoIterator>::IntoIter, n: usize) -> usize {
+    match innerIterator.size_hint( ) {
+      (length, Some(upper)) if length == upper && length < n => n - length,
+
+      (length, Some(upper)) if length == upper => {
+        innerIterator.nth(n - 1);
+        0
+      },
+
+      _ => n - innerIterator.by_ref( ).take(n).count( )
+    }
+  }
 }
 
 impl<O> Iterator for Flatten<O>
@@ -78,12 +327,70 @@ impl<O> Iterator for Flatten<O>
       if let Some(nextFrontIterator)= self.outerIterator.next( ) {
         self.frontInnerIterator= Some(nextFrontIterator.into_iter( ));
       }
-      // TODO: Explain.
+      // The outer iterator is exhausted. But next_back might have already pulled out the last inner
+      // iterator and only partially consumed it. The remaining items of that one come next.
       else {
-        return self.backInnerIterator.as_mut( )?.next( );
+        let nextItem= self.backInnerIterator.as_mut( )?.next( );
+
+        if nextItem.is_none( ) {
+          self.backInnerIterator= None;
+        }
+        return nextItem;
       }
     }
   }
+
+  // The inner iterators which are already in progress tell us how many items are left in them. But
+  // we can't tell anything about the ones which are yet to be pulled out of the outer iterator,
+  // unless there are none.
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    let innerSizeHint= |innerIterator: &Option<_>| {
+      innerIterator.as_ref( ).map_or((0, Some(0)), Iterator::size_hint)};
+
+    let (frontLower, frontUpper)= innerSizeHint(&self.frontInnerIterator);
+    let (backLower, backUpper)= innerSizeHint(&self.backInnerIterator);
+
+    let lower= frontLower.saturating_add(backLower);
+
+    match (self.outerIterator.size_hint( ), frontUpper, backUpper) {
+      ((_, Some(0)), Some(frontUpper), Some(backUpper)) => {
+        (lower, frontUpper.checked_add(backUpper))},
+
+      _ => (lower, None)
+    }
+  }
+
+  // Counts each inner iterator straight away, without going through next for every item.
+  fn count(self) -> usize {
+    let front= self.frontInnerIterator.map_or(0, Iterator::count);
+    let back= self.backInnerIterator.map_or(0, Iterator::count);
+
+    let middle: usize= self.outerIterator
+      .map(|innerIterator| innerIterator.into_iter( ).count( ))
+      .sum( );
+
+    front + middle + back
+  }
+
+  fn nth(&mut self, n: usize) -> Option<Self::Item> {
+    if self.advanceFrontBy(n) > 0 {
+      return None}
+
+    self.next( )
+  }
+
+  // Items of the back inner iterator (if any) come after everything else. So, if it has any left,
+  // its last item is the answer straight away.
+  fn last(self) -> Option<Self::Item> {
+    if let Some(item)= self.backInnerIterator.and_then(Iterator::last) {
+      return Some(item)}
+
+    let middle= self.outerIterator
+      .filter_map(|innerIterator| innerIterator.into_iter( ).last( ))
+      .last( );
+
+    middle.or_else(|| self.frontInnerIterator.and_then(Iterator::last))
+  }
 }
 
 impl<O> DoubleEndedIterator for Flatten<O>
@@ -106,10 +413,2754 @@ impl<O> DoubleEndedIterator for Flatten<O>
       if let Some(nextBackIterator)= self.outerIterator.next_back( ) {
         self.backInnerIterator= Some(nextBackIterator.into_iter( ));
       }
-      // TODO: Explain.
+      // Likewise, the remaining items of the inner iterator which next has started on.
+      else {
+        let nextItem= self.frontInnerIterator.as_mut( )?.next_back( );
+
+        if nextItem.is_none( ) {
+          self.frontInnerIterator= None;
+        }
+        return nextItem;
+      }
+    }
+  }
+}
+
+// Exhausted inner iterators are dropped right away, and the outer iterator is fused. So, nothing is
+// ever polled again after returning None.
+impl<O> FusedIterator for Flatten<O>
+  where O: Iterator,
+        O::Item: IntoIterator
+{ }
+
+// Not derived, since derive would require O::Item to be Clone / Debug as well. What's actually
+// stored is O and the inner iterators.
+impl<O> Clone for Flatten<O>
+  where O: Iterator + Clone,
+        O::Item: IntoIterator,
+        <O::Item as IntoIterator>::IntoIter: Clone
+{
+  fn clone(&self) -> Self {
+    Self {
+      outerIterator: self.outerIterator.clone( ),
+      frontInnerIterator: self.frontInnerIterator.clone( ),
+      backInnerIterator: self.backInnerIterator.clone( )
+    }
+  }
+}
+
+impl<O> fmt::Debug for Flatten<O>
+  where O: Iterator + fmt::Debug,
+        O::Item: IntoIterator,
+        <O::Item as IntoIterator>::IntoIter: fmt::Debug
+{
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_struct("Flatten")
+      .field("outerIterator", &self.outerIterator)
+      .field("frontInnerIterator", &self.frontInnerIterator)
+      .field("backInnerIterator", &self.backInnerIterator)
+      .finish( )
+  }
+}
+
+//---
+
+pub fn flat_map<I, F, U>(iterator: I, f: F) -> FlatMap<I::IntoIter, F, U>
+  where I: IntoIterator,
+        F: FnMut(I::Item) -> U,
+        U: IntoIterator
+{
+  FlatMap::new(iterator.into_iter( ), f)
+}
+
+// Same as Flatten, except that each item of the outer iterator is first turned into an inner
+// iterator by f. f is called only when an inner iterator is needed.
+pub struct FlatMap<O, F, U>
+  where O: Iterator,
+        F: FnMut(O::Item) -> U,
+        U: IntoIterator
+{
+  outerIterator: Fuse<O>,
+  f: F,
+
+  frontInnerIterator: Option<U::IntoIter>,
+  backInnerIterator: Option<U::IntoIter>
+}
+
+impl<O, F, U> FlatMap<O, F, U>
+  where O: Iterator,
+        F: FnMut(O::Item) -> U,
+        U: IntoIterator
+{
+  pub fn new(iterator: O, f: F) -> Self {
+    Self {
+      outerIterator: iterator.fuse( ),
+      f,
+
+      frontInnerIterator: None,
+      backInnerIterator: None
+    }
+  }
+}
+
+impl<O, F, U> Iterator for FlatMap<O, F, U>
+  where O: Iterator,
+        F: FnMut(O::Item) -> U,
+        U: IntoIterator
+{
+  type Item = U::Item;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    loop {
+      if let Some(ref mut innerIterator)= self.frontInnerIterator {
+        let nextItem= innerIterator.next( );
+
+        if nextItem.is_some( ) {
+          return nextItem;
+        }
+
+        self.frontInnerIterator= None;
+      }
+
+      if let Some(nextOuterItem)= self.outerIterator.next( ) {
+        self.frontInnerIterator= Some((self.f)(nextOuterItem).into_iter( ));
+      }
+      // See Flatten::next.
+      else {
+        let nextItem= self.backInnerIterator.as_mut( )?.next( );
+
+        if nextItem.is_none( ) {
+          self.backInnerIterator= None;
+        }
+        return nextItem;
+      }
+    }
+  }
+
+  // We can't tell how many items f is going to produce for the items left in the outer iterator.
+  // So, the upper bound is known only once the outer iterator is exhausted.
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    let innerSizeHint= |innerIterator: &Option<_>| {
+      innerIterator.as_ref( ).map_or((0, Some(0)), Iterator::size_hint)};
+
+    let (frontLower, frontUpper)= innerSizeHint(&self.frontInnerIterator);
+    let (backLower, backUpper)= innerSizeHint(&self.backInnerIterator);
+
+    let lower= frontLower.saturating_add(backLower);
+
+    match (self.outerIterator.size_hint( ), frontUpper, backUpper) {
+      ((_, Some(0)), Some(frontUpper), Some(backUpper)) => {
+        (lower, frontUpper.checked_add(backUpper))},
+
+      _ => (lower, None)
+    }
+  }
+}
+
+impl<O, F, U> DoubleEndedIterator for FlatMap<O, F, U>
+  where O: DoubleEndedIterator,
+        F: FnMut(O::Item) -> U,
+        U: IntoIterator,
+        U::IntoIter: DoubleEndedIterator
+{
+  fn next_back(&mut self) -> Option<Self::Item> {
+    loop {
+      if let Some(ref mut innerIterator)= self.backInnerIterator {
+        let nextItem= innerIterator.next_back( );
+
+        if nextItem.is_some( ) {
+          return nextItem;
+        }
+
+        self.backInnerIterator= None;
+      }
+
+      if let Some(nextOuterItem)= self.outerIterator.next_back( ) {
+        self.backInnerIterator= Some((self.f)(nextOuterItem).into_iter( ));
+      }
       else {
-        return self.frontInnerIterator.as_mut( )?.next_back( );
+        let nextItem= self.frontInnerIterator.as_mut( )?.next_back( );
+
+        if nextItem.is_none( ) {
+          self.frontInnerIterator= None;
+        }
+        return nextItem;
       }
     }
   }
-}
\ No newline at end of file
+}
+
+impl<O, F, U> FusedIterator for FlatMap<O, F, U>
+  where O: Iterator,
+        F: FnMut(O::Item) -> U,
+        U: IntoIterator
+{ }
+
+impl<O, F, U> Clone for FlatMap<O, F, U>
+  where O: Iterator + Clone,
+        F: FnMut(O::Item) -> U + Clone,
+        U: IntoIterator,
+        U::IntoIter: Clone
+{
+  fn clone(&self) -> Self {
+    Self {
+      outerIterator: self.outerIterator.clone( ),
+      f: self.f.clone( ),
+
+      frontInnerIterator: self.frontInnerIterator.clone( ),
+      backInnerIterator: self.backInnerIterator.clone( )
+    }
+  }
+}
+
+// f is left out, since closures aren't Debug.
+impl<O, F, U> fmt::Debug for FlatMap<O, F, U>
+  where O: Iterator + fmt::Debug,
+        F: FnMut(O::Item) -> U,
+        U: IntoIterator,
+        U::IntoIter: fmt::Debug
+{
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_struct("FlatMap")
+      .field("outerIterator", &self.outerIterator)
+      .field("frontInnerIterator", &self.frontInnerIterator)
+      .field("backInnerIterator", &self.backInnerIterator)
+      .finish_non_exhaustive( )
+  }
+}
+
+//---
+
+pub fn interleave<A, B>(a: A, b: B) -> Interleave<A::IntoIter, B::IntoIter>
+  where A: IntoIterator,
+        B: IntoIterator<Item = A::Item>
+{
+  Interleave::new(a.into_iter( ), b.into_iter( ))
+}
+
+// Yields items from a and b alternately (starting with a). Once either of them is exhausted, the
+// rest of the other one follows.
+#[derive(Clone, Debug)]
+pub struct Interleave<A, B> {
+  a: Fuse<A>,
+  b: Fuse<B>,
+
+  // Whose turn it is, from the front.
+  nextFromB: bool
+}
+
+impl<A, B> Interleave<A, B>
+  where A: Iterator,
+        B: Iterator<Item = A::Item>
+{
+  pub fn new(a: A, b: B) -> Self {
+    Self {
+      a: a.fuse( ),
+      b: b.fuse( ),
+      nextFromB: false
+    }
+  }
+}
+
+impl<A, B> Iterator for Interleave<A, B>
+  where A: Iterator,
+        B: Iterator<Item = A::Item>
+{
+  type Item = A::Item;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    let nextItem= if self.nextFromB { self.b.next( ) } else { self.a.next( ) };
+
+    match nextItem {
+      Some(item) => {
+        self.nextFromB= !self.nextFromB;
+        Some(item)
+      },
+
+      // It isn't their turn, but the other one is all that's left.
+      None if self.nextFromB => self.a.next( ),
+      None => self.b.next( )
+    }
+  }
+
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    let (aLower, aUpper)= self.a.size_hint( );
+    let (bLower, bUpper)= self.b.size_hint( );
+
+    let upper= match (aUpper, bUpper) {
+      (Some(aUpper), Some(bUpper)) => aUpper.checked_add(bUpper),
+      _ => None
+    };
+    (aLower.saturating_add(bLower), upper)
+  }
+}
+
+// To figure out whether the last item comes from a or b, we need to know how many items are left in
+// each : the one with more items left ends the sequence (with the leftovers). With equal lengths,
+// the last pair ends the sequence, whose second item comes from whoever's turn it isn't.
+// Taking items from the back doesn't affect whose turn it is from the front.
+impl<A, B> DoubleEndedIterator for Interleave<A, B>
+  where A: DoubleEndedIterator + ExactSizeIterator,
+        B: DoubleEndedIterator<Item = A::Item> + ExactSizeIterator
+{
+  fn next_back(&mut self) -> Option<Self::Item> {
+    let (aLength, bLength)= (self.a.len( ), self.b.len( ));
+
+    let fromA= if self.nextFromB { aLength >= bLength } else { aLength > bLength };
+
+    if fromA {
+      self.a.next_back( )}
+    else {
+      self.b.next_back( )}
+  }
+}
+
+impl<A, B> FusedIterator for Interleave<A, B>
+  where A: Iterator,
+        B: Iterator<Item = A::Item>
+{ }
+
+//---
+
+// Groups the items into Vecs of the given size. The last one is shorter, if the number of items
+// isn't a multiple of the size.
+#[derive(Clone, Debug)]
+pub struct Chunks<I> {
+  iterator: Fuse<I>,
+  size: usize
+}
+
+impl<I> Chunks<I>
+  where I: Iterator
+{
+  pub fn new(iterator: I, size: usize) -> Self {
+    assert!(size != 0, "chunk size must be non-zero");
+
+    Self {
+      iterator: iterator.fuse( ),
+      size
+    }
+  }
+}
+
+impl<I> Iterator for Chunks<I>
+  where I: Iterator
+{
+  type Item = Vec<I::Item>;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    let chunk: Vec<_>= self.iterator.by_ref( ).take(self.size).collect( );
+
+    if chunk.is_empty( ) {
+      return None}
+    Some(chunk)
+  }
+
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    let (lower, upper)= self.iterator.size_hint( );
+
+    (lower.div_ceil(self.size), upper.map(|upper| upper.div_ceil(self.size)))
+  }
+}
+
+impl<I> ExactSizeIterator for Chunks<I>
+  where I: ExactSizeIterator
+{ }
+
+impl<I> FusedIterator for Chunks<I>
+  where I: Iterator
+{ }
+
+//---
+
+// Yields the last 'size' items (as a Vec), every time a new item comes in. Nothing is yielded if
+// there are less than 'size' items.
+pub struct WindowsIter<I>
+  where I: Iterator
+{
+  iterator: Fuse<I>,
+  size: usize,
+
+  window: VecDeque<I::Item>
+}
+
+impl<I> WindowsIter<I>
+  where I: Iterator,
+        I::Item: Clone
+{
+  pub fn new(iterator: I, size: usize) -> Self {
+    assert!(size != 0, "window size must be non-zero");
+
+    Self {
+      iterator: iterator.fuse( ),
+      size,
+      window: VecDeque::with_capacity(size)
+    }
+  }
+}
+
+impl<I> Iterator for WindowsIter<I>
+  where I: Iterator,
+        I::Item: Clone
+{
+  type Item = Vec<I::Item>;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    // The window slides forward by 1 item. Except the very first time, when it needs to be filled
+    // up.
+    if self.window.len( ) == self.size {
+      self.window.pop_front( );
+    }
+
+    while self.window.len( ) < self.size {
+      self.window.push_back(self.iterator.next( )?);
+    }
+
+    Some(self.window.iter( ).cloned( ).collect( ))
+  }
+
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    let (lower, upper)= self.iterator.size_hint( );
+
+    // Once the window is full, each remaining item makes a new window.
+    if self.window.len( ) == self.size {
+      return (lower, upper)}
+
+    let windows= |items: usize| {
+      items.saturating_add(self.window.len( )).saturating_sub(self.size - 1)};
+    (windows(lower), upper.map(windows))
+  }
+}
+
+impl<I> FusedIterator for WindowsIter<I>
+  where I: Iterator,
+        I::Item: Clone
+{ }
+
+impl<I> Clone for WindowsIter<I>
+  where I: Iterator + Clone,
+        I::Item: Clone
+{
+  fn clone(&self) -> Self {
+    Self {
+      iterator: self.iterator.clone( ),
+      size: self.size,
+      window: self.window.clone( )
+    }
+  }
+}
+
+impl<I> fmt::Debug for WindowsIter<I>
+  where I: Iterator + fmt::Debug,
+        I::Item: fmt::Debug
+{
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_struct("WindowsIter")
+      .field("iterator", &self.iterator)
+      .field("size", &self.size)
+      .field("window", &self.window)
+      .finish( )
+  }
+}
+
+//---
+
+// Item of ZipLongest.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EitherOrBoth<L, R> {
+  Both(L, R),
+
+  // The right side is exhausted.
+  Left(L),
+
+  // The left side is exhausted.
+  Right(R)
+}
+
+impl<L, R> EitherOrBoth<L, R> {
+  pub fn left(self) -> Option<L> {
+    match self {
+      EitherOrBoth::Both(l, _) | EitherOrBoth::Left(l) => Some(l),
+      EitherOrBoth::Right(_) => None
+    }
+  }
+
+  pub fn right(self) -> Option<R> {
+    match self {
+      EitherOrBoth::Both(_, r) | EitherOrBoth::Right(r) => Some(r),
+      EitherOrBoth::Left(_) => None
+    }
+  }
+
+  pub fn is_both(&self) -> bool {
+    matches!(self, EitherOrBoth::Both(..))
+  }
+
+  // Fills in the missing side with the given value.
+  pub fn or(self, l: L, r: R) -> (L, R) {
+    match self {
+      EitherOrBoth::Both(l, r) => (l, r),
+      EitherOrBoth::Left(l) => (l, r),
+      EitherOrBoth::Right(r) => (l, r)
+    }
+  }
+
+  pub fn or_default(self) -> (L, R)
+    where L: Default,
+          R: Default
+  {
+    match self {
+      EitherOrBoth::Both(l, r) => (l, r),
+      EitherOrBoth::Left(l) => (l, R::default( )),
+      EitherOrBoth::Right(r) => (L::default( ), r)
+    }
+  }
+}
+
+// Like zip, but keeps going till both the sides are exhausted.
+#[derive(Clone, Debug)]
+pub struct ZipLongest<A, B> {
+  a: Fuse<A>,
+  b: Fuse<B>
+}
+
+impl<A, B> ZipLongest<A, B>
+  where A: Iterator,
+        B: Iterator
+{
+  pub fn new(a: A, b: B) -> Self {
+    Self {
+      a: a.fuse( ),
+      b: b.fuse( )
+    }
+  }
+}
+
+impl<A, B> Iterator for ZipLongest<A, B>
+  where A: Iterator,
+        B: Iterator
+{
+  type Item = EitherOrBoth<A::Item, B::Item>;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    match (self.a.next( ), self.b.next( )) {
+      (Some(l), Some(r)) => Some(EitherOrBoth::Both(l, r)),
+      (Some(l), None) => Some(EitherOrBoth::Left(l)),
+      (None, Some(r)) => Some(EitherOrBoth::Right(r)),
+      (None, None) => None
+    }
+  }
+
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    let (aLower, aUpper)= self.a.size_hint( );
+    let (bLower, bUpper)= self.b.size_hint( );
+
+    let upper= match (aUpper, bUpper) {
+      (Some(aUpper), Some(bUpper)) => Some(aUpper.max(bUpper)),
+      _ => None
+    };
+    (aLower.max(bLower), upper)
+  }
+}
+
+// The longer side's tail sticks out at the back. So, we take from that side alone, till both the
+// sides are of the same length.
+impl<A, B> DoubleEndedIterator for ZipLongest<A, B>
+  where A: DoubleEndedIterator + ExactSizeIterator,
+        B: DoubleEndedIterator + ExactSizeIterator
+{
+  fn next_back(&mut self) -> Option<Self::Item> {
+    match self.a.len( ).cmp(&self.b.len( )) {
+      Ordering::Greater => self.a.next_back( ).map(EitherOrBoth::Left),
+      Ordering::Less => self.b.next_back( ).map(EitherOrBoth::Right),
+
+      Ordering::Equal => {
+        let (l, r)= (self.a.next_back( )?, self.b.next_back( )?);
+        Some(EitherOrBoth::Both(l, r))
+      }
+    }
+  }
+}
+
+impl<A, B> ExactSizeIterator for ZipLongest<A, B>
+  where A: ExactSizeIterator,
+        B: ExactSizeIterator
+{ }
+
+impl<A, B> FusedIterator for ZipLongest<A, B>
+  where A: Iterator,
+        B: Iterator
+{ }
+
+//---
+
+// Puts a separator in between every 2 consecutive items.
+pub struct Intersperse<I>
+  where I: Iterator
+{
+  state: IntersperseState<I>,
+  separator: I::Item
+}
+
+impl<I> Iterator for Intersperse<I>
+  where I: Iterator,
+        I::Item: Clone
+{
+  type Item = I::Item;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    self.state.next(|| self.separator.clone( ))
+  }
+
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    self.state.size_hint( )
+  }
+}
+
+impl<I> FusedIterator for Intersperse<I>
+  where I: Iterator,
+        I::Item: Clone
+{ }
+
+impl<I> Clone for Intersperse<I>
+  where I: Iterator + Clone,
+        I::Item: Clone
+{
+  fn clone(&self) -> Self {
+    Self {
+      state: self.state.clone( ),
+      separator: self.separator.clone( )
+    }
+  }
+}
+
+impl<I> fmt::Debug for Intersperse<I>
+  where I: Iterator + fmt::Debug,
+        I::Item: fmt::Debug
+{
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_struct("Intersperse")
+      .field("state", &self.state)
+      .field("separator", &self.separator)
+      .finish( )
+  }
+}
+
+pub struct IntersperseWith<I, F>
+  where I: Iterator
+{
+  state: IntersperseState<I>,
+  separator: F
+}
+
+impl<I, F> Iterator for IntersperseWith<I, F>
+  where I: Iterator,
+        F: FnMut( ) -> I::Item
+{
+  type Item = I::Item;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    self.state.next(&mut self.separator)
+  }
+
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    self.state.size_hint( )
+  }
+}
+
+impl<I, F> FusedIterator for IntersperseWith<I, F>
+  where I: Iterator,
+        F: FnMut( ) -> I::Item
+{ }
+
+impl<I, F> Clone for IntersperseWith<I, F>
+  where I: Iterator + Clone,
+        I::Item: Clone,
+        F: Clone
+{
+  fn clone(&self) -> Self {
+    Self {
+      state: self.state.clone( ),
+      separator: self.separator.clone( )
+    }
+  }
+}
+
+// The separator closure is left out, since closures aren't Debug.
+impl<I, F> fmt::Debug for IntersperseWith<I, F>
+  where I: Iterator + fmt::Debug,
+        I::Item: fmt::Debug
+{
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_struct("IntersperseWith")
+      .field("state", &self.state)
+      .finish_non_exhaustive( )
+  }
+}
+
+// The state machine shared by Intersperse and IntersperseWith. To know whether a separator is due,
+// we need to know whether another item follows. So, that item gets pulled out in advance and is
+// kept aside, till the separator has been yielded.
+struct IntersperseState<I>
+  where I: Iterator
+{
+  iterator: Fuse<I>,
+  peeked: Option<I::Item>,
+
+  // Whether the first item has been yielded. No separator goes before it.
+  started: bool
+}
+
+impl<I> IntersperseState<I>
+  where I: Iterator
+{
+  fn new(iterator: I) -> Self {
+    Self {
+      iterator: iterator.fuse( ),
+      peeked: None,
+      started: false
+    }
+  }
+
+  fn next(&mut self, separator: impl FnOnce( ) -> I::Item) -> Option<I::Item> {
+    if !self.started {
+      self.started= true;
+      return self.iterator.next( );
+    }
+
+    if let Some(item)= self.peeked.take( ) {
+      return Some(item)}
+
+    self.peeked= Some(self.iterator.next( )?);
+    Some(separator( ))
+  }
+
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    let (lower, upper)= self.iterator.size_hint( );
+
+    // Before the first item, n items need n - 1 separators. Afterwards, each of the remaining items
+    // comes with a separator (the peeked one already had its separator yielded).
+    let withSeparators= |items: usize| {
+      if self.started {
+        items.checked_mul(2)?.checked_add(self.peeked.is_some( ) as usize)}
+      else {
+        Some(items.checked_mul(2)?.saturating_sub(1))}
+    };
+
+    (withSeparators(lower).unwrap_or(usize::MAX), upper.and_then(withSeparators))
+  }
+}
+
+impl<I> Clone for IntersperseState<I>
+  where I: Iterator + Clone,
+        I::Item: Clone
+{
+  fn clone(&self) -> Self {
+    Self {
+      iterator: self.iterator.clone( ),
+      peeked: self.peeked.clone( ),
+      started: self.started
+    }
+  }
+}
+
+impl<I> fmt::Debug for IntersperseState<I>
+  where I: Iterator + fmt::Debug,
+        I::Item: fmt::Debug
+{
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_struct("IntersperseState")
+      .field("iterator", &self.iterator)
+      .field("peeked", &self.peeked)
+      .field("started", &self.started)
+      .finish( )
+  }
+}
+
+//---
+
+// Groups runs of consecutive items having the same key. Each group is yielded as an owned Vec,
+// along with the key.
+pub struct ChunkBy<I, K, F>
+  where I: Iterator
+{
+  iterator: Fuse<I>,
+  keyFn: F,
+
+  // The first item of the next group (along with its key). To find out where a group ends, we
+  // need to pull out the item after it.
+  pending: Option<(K, I::Item)>
+}
+
+impl<I, K, F> Iterator for ChunkBy<I, K, F>
+  where I: Iterator,
+        K: PartialEq,
+        F: FnMut(&I::Item) -> K
+{
+  type Item = (K, Vec<I::Item>);
+
+  fn next(&mut self) -> Option<Self::Item> {
+    let (key, firstItem)= match self.pending.take( ) {
+      Some(pending) => pending,
+
+      None => {
+        let item= self.iterator.next( )?;
+        ((self.keyFn)(&item), item)
+      }
+    };
+
+    let mut group= vec![ firstItem ];
+    for item in self.iterator.by_ref( ) {
+      let itemKey= (self.keyFn)(&item);
+
+      if itemKey != key {
+        self.pending= Some((itemKey, item));
+        break
+      }
+      group.push(item);
+    }
+
+    Some((key, group))
+  }
+
+  // Anything from all the items being in a single group, to each item being in a group of its own.
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    let (lower, upper)= self.iterator.size_hint( );
+    let pending= self.pending.is_some( ) as usize;
+
+    ((pending + lower).min(1), upper.and_then(|upper| upper.checked_add(pending)))
+  }
+}
+
+impl<I, K, F> FusedIterator for ChunkBy<I, K, F>
+  where I: Iterator,
+        K: PartialEq,
+        F: FnMut(&I::Item) -> K
+{ }
+
+impl<I, K, F> Clone for ChunkBy<I, K, F>
+  where I: Iterator + Clone,
+        I::Item: Clone,
+        K: Clone,
+        F: Clone
+{
+  fn clone(&self) -> Self {
+    Self {
+      iterator: self.iterator.clone( ),
+      keyFn: self.keyFn.clone( ),
+      pending: self.pending.clone( )
+    }
+  }
+}
+
+// The key function is left out, since closures aren't Debug.
+impl<I, K, F> fmt::Debug for ChunkBy<I, K, F>
+  where I: Iterator + fmt::Debug,
+        I::Item: fmt::Debug,
+        K: fmt::Debug
+{
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_struct("ChunkBy")
+      .field("iterator", &self.iterator)
+      .field("pending", &self.pending)
+      .finish_non_exhaustive( )
+  }
+}
+
+//---
+
+pub type Merge<A, B>= MergeBy<A, B, fn(&<A as Iterator>::Item, &<A as Iterator>::Item) -> Ordering>;
+
+// Merges 2 sorted iterators into a single sorted one. The merge is stable : when items compare
+// equal, the one from a comes first.
+pub struct MergeBy<A, B, F>
+  where A: Iterator
+{
+  a: Fuse<A>,
+  b: Fuse<B>,
+  comparator: F,
+
+  // The next item of each side, pulled out in advance to be compared against the other's.
+  aPeeked: Option<A::Item>,
+  bPeeked: Option<A::Item>
+}
+
+impl<A, B, F> MergeBy<A, B, F>
+  where A: Iterator,
+        B: Iterator<Item = A::Item>,
+        F: FnMut(&A::Item, &A::Item) -> Ordering
+{
+  pub fn new(a: A, b: B, comparator: F) -> Self {
+    Self {
+      a: a.fuse( ),
+      b: b.fuse( ),
+      comparator,
+
+      aPeeked: None,
+      bPeeked: None
+    }
+  }
+}
+
+impl<A, B, F> Iterator for MergeBy<A, B, F>
+  where A: Iterator,
+        B: Iterator<Item = A::Item>,
+        F: FnMut(&A::Item, &A::Item) -> Ordering
+{
+  type Item = A::Item;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    if self.aPeeked.is_none( ) {
+      self.aPeeked= self.a.next( )}
+
+    if self.bPeeked.is_none( ) {
+      self.bPeeked= self.b.next( )}
+
+    match (&self.aPeeked, &self.bPeeked) {
+      (Some(aItem), Some(bItem)) => {
+        if (self.comparator)(aItem, bItem) == Ordering::Greater {
+          self.bPeeked.take( )}
+        else {
+          self.aPeeked.take( )}
+      },
+
+      (Some(_), None) => self.aPeeked.take( ),
+      (None, _) => self.bPeeked.take( )
+    }
+  }
+
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    let peeked= self.aPeeked.is_some( ) as usize + self.bPeeked.is_some( ) as usize;
+
+    let (aLower, aUpper)= self.a.size_hint( );
+    let (bLower, bUpper)= self.b.size_hint( );
+
+    let lower= aLower.saturating_add(bLower).saturating_add(peeked);
+    let upper= match (aUpper, bUpper) {
+      (Some(aUpper), Some(bUpper)) => aUpper.checked_add(bUpper).and_then(|upper| {
+        upper.checked_add(peeked)}),
+      _ => None
+    };
+    (lower, upper)
+  }
+}
+
+impl<A, B, F> FusedIterator for MergeBy<A, B, F>
+  where A: Iterator,
+        B: Iterator<Item = A::Item>,
+        F: FnMut(&A::Item, &A::Item) -> Ordering
+{ }
+
+impl<A, B, F> Clone for MergeBy<A, B, F>
+  where A: Iterator + Clone,
+        A::Item: Clone,
+        B: Clone,
+        F: Clone
+{
+  fn clone(&self) -> Self {
+    Self {
+      a: self.a.clone( ),
+      b: self.b.clone( ),
+      comparator: self.comparator.clone( ),
+
+      aPeeked: self.aPeeked.clone( ),
+      bPeeked: self.bPeeked.clone( )
+    }
+  }
+}
+
+// The comparator is left out, since closures aren't Debug.
+impl<A, B, F> fmt::Debug for MergeBy<A, B, F>
+  where A: Iterator + fmt::Debug,
+        A::Item: fmt::Debug,
+        B: fmt::Debug
+{
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_struct("MergeBy")
+      .field("a", &self.a)
+      .field("b", &self.b)
+      .field("aPeeked", &self.aPeeked)
+      .field("bPeeked", &self.bPeeked)
+      .finish_non_exhaustive( )
+  }
+}
+
+//---
+
+// Like Peekable, but can look ahead as far as we want. Each peek looks 1 item further ahead, till
+// reset_peek or next brings the lookahead back to the very next item.
+pub struct MultiPeek<I>
+  where I: Iterator
+{
+  iterator: Fuse<I>,
+
+  // Items which have been peeked at, but not yet yielded. In order.
+  buffer: VecDeque<I::Item>,
+
+  // Index (into the buffer) of the item which the next peek returns.
+  cursor: usize
+}
+
+impl<I> MultiPeek<I>
+  where I: Iterator
+{
+  pub fn peek(&mut self) -> Option<&I::Item> {
+    if self.cursor == self.buffer.len( ) {
+      self.buffer.push_back(self.iterator.next( )?);
+    }
+
+    self.cursor += 1;
+    self.buffer.get(self.cursor - 1)
+  }
+
+  pub fn reset_peek(&mut self) {
+    self.cursor= 0;
+  }
+}
+
+impl<I> Iterator for MultiPeek<I>
+  where I: Iterator
+{
+  type Item = I::Item;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    self.cursor= 0;
+
+    match self.buffer.pop_front( ) {
+      Some(item) => Some(item),
+      None => self.iterator.next( )
+    }
+  }
+
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    let (lower, upper)= self.iterator.size_hint( );
+    let buffered= self.buffer.len( );
+
+    (lower.saturating_add(buffered), upper.and_then(|upper| upper.checked_add(buffered)))
+  }
+}
+
+impl<I> FusedIterator for MultiPeek<I>
+  where I: Iterator
+{ }
+
+impl<I> Clone for MultiPeek<I>
+  where I: Iterator + Clone,
+        I::Item: Clone
+{
+  fn clone(&self) -> Self {
+    Self {
+      iterator: self.iterator.clone( ),
+      buffer: self.buffer.clone( ),
+      cursor: self.cursor
+    }
+  }
+}
+
+impl<I> fmt::Debug for MultiPeek<I>
+  where I: Iterator + fmt::Debug,
+        I::Item: fmt::Debug
+{
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_struct("MultiPeek")
+      .field("iterator", &self.iterator)
+      .field("buffer", &self.buffer)
+      .field("cursor", &self.cursor)
+      .finish( )
+  }
+}
+
+//---
+
+// Like take_while, but the first item for which the predicate fails is yielded as well.
+#[derive(Clone)]
+pub struct TakeWhileInclusive<I, P> {
+  iterator: I,
+  predicate: P,
+
+  // Set once the predicate fails, or the iterator is exhausted.
+  done: bool
+}
+
+impl<I, P> Iterator for TakeWhileInclusive<I, P>
+  where I: Iterator,
+        P: FnMut(&I::Item) -> bool
+{
+  type Item = I::Item;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    if self.done {
+      return None}
+
+    match self.iterator.next( ) {
+      Some(item) => {
+        self.done= !(self.predicate)(&item);
+        Some(item)
+      },
+
+      None => {
+        self.done= true;
+        None
+      }
+    }
+  }
+
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    if self.done {
+      return (0, Some(0))}
+
+    (0, self.iterator.size_hint( ).1)
+  }
+}
+
+impl<I, P> FusedIterator for TakeWhileInclusive<I, P>
+  where I: Iterator,
+        P: FnMut(&I::Item) -> bool
+{ }
+
+// The predicate is left out, since closures aren't Debug.
+impl<I, P> fmt::Debug for TakeWhileInclusive<I, P>
+  where I: fmt::Debug
+{
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_struct("TakeWhileInclusive")
+      .field("iterator", &self.iterator)
+      .field("done", &self.done)
+      .finish_non_exhaustive( )
+  }
+}
+
+//---
+
+// Yields only the first occurrence of each item.
+pub struct Unique<I>
+  where I: Iterator
+{
+  iterator: I,
+  seen: HashSet<I::Item>
+}
+
+impl<I> Iterator for Unique<I>
+  where I: Iterator,
+        I::Item: Clone + Eq + Hash
+{
+  type Item = I::Item;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    self.iterator.by_ref( ).find(|item| self.seen.insert(item.clone( )))
+  }
+
+  // All the remaining items might be duplicates. But if there's at least 1 item left and none of
+  // them have been seen yet, then we can't tell.
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    let (lower, upper)= self.iterator.size_hint( );
+    ((lower > 0 && self.seen.is_empty( )) as usize, upper)
+  }
+}
+
+impl<I> FusedIterator for Unique<I>
+  where I: FusedIterator,
+        I::Item: Clone + Eq + Hash
+{ }
+
+impl<I> Clone for Unique<I>
+  where I: Iterator + Clone,
+        I::Item: Clone
+{
+  fn clone(&self) -> Self {
+    Self {
+      iterator: self.iterator.clone( ),
+      seen: self.seen.clone( )
+    }
+  }
+}
+
+impl<I> fmt::Debug for Unique<I>
+  where I: Iterator + fmt::Debug,
+        I::Item: fmt::Debug
+{
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_struct("Unique")
+      .field("iterator", &self.iterator)
+      .field("seen", &self.seen)
+      .finish( )
+  }
+}
+
+// Yields only the first item having each key.
+#[derive(Clone)]
+pub struct UniqueBy<I, K, F> {
+  iterator: I,
+  seen: HashSet<K>,
+  keyFn: F
+}
+
+impl<I, K, F> Iterator for UniqueBy<I, K, F>
+  where I: Iterator,
+        K: Eq + Hash,
+        F: FnMut(&I::Item) -> K
+{
+  type Item = I::Item;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    self.iterator.by_ref( ).find(|item| self.seen.insert((self.keyFn)(item)))
+  }
+
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    let (lower, upper)= self.iterator.size_hint( );
+    ((lower > 0 && self.seen.is_empty( )) as usize, upper)
+  }
+}
+
+impl<I, K, F> FusedIterator for UniqueBy<I, K, F>
+  where I: FusedIterator,
+        K: Eq + Hash,
+        F: FnMut(&I::Item) -> K
+{ }
+
+// The key function is left out, since closures aren't Debug.
+impl<I, K, F> fmt::Debug for UniqueBy<I, K, F>
+  where I: fmt::Debug,
+        K: fmt::Debug
+{
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_struct("UniqueBy")
+      .field("iterator", &self.iterator)
+      .field("seen", &self.seen)
+      .finish_non_exhaustive( )
+  }
+}
+
+//---
+
+pub struct Stride<I> {
+  iterator: Fuse<I>,
+  step: usize,
+
+  // Number of items to be skipped, before the next item from the front is yielded. It's the
+  // offset at first, and step - 1 afterwards.
+  skip: usize
+}
+
+impl<I> Stride<I>
+  where I: Iterator
+{
+  pub fn new(iterator: I, step: usize, offset: usize) -> Self {
+    assert!(step != 0, "step must be non-zero");
+
+    Self {
+      iterator: iterator.fuse( ),
+      step,
+      skip: offset
+    }
+  }
+
+  // Number of items that'll be yielded, given that the inner iterator has remaining items left.
+  fn strideLength(&self, remaining: usize) -> usize {
+    if remaining <= self.skip {
+      return 0}
+
+    (remaining - self.skip - 1) / self.step + 1
+  }
+}
+
+impl<I> Iterator for Stride<I>
+  where I: Iterator
+{
+  type Item = I::Item;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    let item= self.iterator.nth(self.skip)?;
+    self.skip= self.step - 1;
+    Some(item)
+  }
+
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    let (lower, upper)= self.iterator.size_hint( );
+
+    (self.strideLength(lower), upper.map(|upper| self.strideLength(upper)))
+  }
+}
+
+impl<I> DoubleEndedIterator for Stride<I>
+  where I: DoubleEndedIterator + ExactSizeIterator
+{
+  // Positions (in the inner iterator) of the remaining items are skip, skip + step, ...,
+  // skip + (n - 1) * step. So everything after the last one of them needs to be skipped from the
+  // back.
+  fn next_back(&mut self) -> Option<Self::Item> {
+    let remaining= self.iterator.len( );
+
+    let n= self.strideLength(remaining);
+    if n == 0 {
+      return None}
+
+    let lastPosition= self.skip + (n - 1) * self.step;
+    self.iterator.nth_back(remaining - 1 - lastPosition)
+  }
+}
+
+impl<I> ExactSizeIterator for Stride<I>
+  where I: ExactSizeIterator
+{ }
+
+impl<I> FusedIterator for Stride<I>
+  where I: Iterator
+{ }
+
+impl<I> Clone for Stride<I>
+  where I: Clone
+{
+  fn clone(&self) -> Self {
+    Self {
+      iterator: self.iterator.clone( ),
+      step: self.step,
+      skip: self.skip
+    }
+  }
+}
+
+impl<I> fmt::Debug for Stride<I>
+  where I: fmt::Debug
+{
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_struct("Stride")
+      .field("iterator", &self.iterator)
+      .field("step", &self.step)
+      .field("skip", &self.skip)
+      .finish( )
+  }
+}
+
+//---
+
+// NOTE - Batching isn't fused. It calls the closure every time next is called, even after the
+// closure has returned None. So, whether it keeps returning None is upto the closure (and the
+// iterator). Call fuse on it, if that's needed.
+#[derive(Clone)]
+pub struct Batching<I, F> {
+  iterator: I,
+  f: F
+}
+
+impl<I, B, F> Iterator for Batching<I, F>
+  where I: Iterator,
+        F: FnMut(&mut I) -> Option<B>
+{
+  type Item = B;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    (self.f)(&mut self.iterator)
+  }
+
+  // The closure can pull any number of items for each item it yields (and might even yield items
+  // without pulling any). So, we can't tell anything.
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    (0, None)
+  }
+}
+
+// The closure is left out, since closures aren't Debug.
+impl<I, F> fmt::Debug for Batching<I, F>
+  where I: fmt::Debug
+{
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_struct("Batching")
+      .field("iterator", &self.iterator)
+      .finish_non_exhaustive( )
+  }
+}
+
+//---
+
+type MiddleIterator<O>= <<O as Iterator>::Item as IntoIterator>::IntoIter;
+type InnerIterator<O>= <<MiddleIterator<O> as Iterator>::Item as IntoIterator>::IntoIter;
+
+// Flattens exactly 2 levels of nesting. Works just like Flatten, except that both the front and the
+// back have a middle iterator in progress, along with an inner one.
+pub struct FlattenDeep2<O>
+  where O: Iterator,
+        O::Item: IntoIterator,
+        <O::Item as IntoIterator>::Item: IntoIterator
+{
+  outerIterator: Fuse<O>,
+
+  frontMiddleIterator: Option<MiddleIterator<O>>,
+  frontInnerIterator: Option<InnerIterator<O>>,
+
+  backMiddleIterator: Option<MiddleIterator<O>>,
+  backInnerIterator: Option<InnerIterator<O>>
+}
+
+impl<O> FlattenDeep2<O>
+  where O: Iterator,
+        O::Item: IntoIterator,
+        <O::Item as IntoIterator>::Item: IntoIterator
+{
+  pub fn new(iterator: O) -> Self {
+    Self {
+      outerIterator: iterator.fuse( ),
+      frontMiddleIterator: None,
+      frontInnerIterator: None,
+      backMiddleIterator: None,
+      backInnerIterator: None
+    }
+  }
+}
+
+impl<O> Iterator for FlattenDeep2<O>
+  where O: Iterator,
+        O::Item: IntoIterator,
+        <O::Item as IntoIterator>::Item: IntoIterator
+{
+  type Item = <InnerIterator<O> as Iterator>::Item;
+
+  // Items come from : the front inner iterator -> the front middle iterator -> the outer iterator
+  // -> the back middle iterator (what's left of it, after next_back has pulled out some of its
+  // inner iterators) -> the back inner iterator.
+  fn next(&mut self) -> Option<Self::Item> {
+    loop {
+      if let Some(ref mut innerIterator)= self.frontInnerIterator {
+        let nextItem= innerIterator.next( );
+
+        if nextItem.is_some( ) {
+          return nextItem}
+
+        self.frontInnerIterator= None;
+      }
+
+      if let Some(ref mut middleIterator)= self.frontMiddleIterator {
+        if let Some(nextInnerIterator)= middleIterator.next( ) {
+          self.frontInnerIterator= Some(nextInnerIterator.into_iter( ));
+          continue;
+        }
+
+        self.frontMiddleIterator= None;
+      }
+
+      if let Some(nextMiddleIterator)= self.outerIterator.next( ) {
+        self.frontMiddleIterator= Some(nextMiddleIterator.into_iter( ));
+        continue;
+      }
+
+      if let Some(ref mut middleIterator)= self.backMiddleIterator {
+        if let Some(nextInnerIterator)= middleIterator.next( ) {
+          self.frontInnerIterator= Some(nextInnerIterator.into_iter( ));
+          continue;
+        }
+
+        self.backMiddleIterator= None;
+      }
+
+      let nextItem= self.backInnerIterator.as_mut( )?.next( );
+
+      if nextItem.is_none( ) {
+        self.backInnerIterator= None;
+      }
+      return nextItem;
+    }
+  }
+
+  // Like Flatten, only the inner iterators in progress can tell us anything. And we get an upper
+  // bound only when there's nothing left in the outer and the middle iterators.
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    let innerSizeHint= |innerIterator: &Option<InnerIterator<O>>| {
+      innerIterator.as_ref( ).map_or((0, Some(0)), Iterator::size_hint)};
+
+    let isEmpty= |middleIterator: &Option<MiddleIterator<O>>| {
+      middleIterator.as_ref( )
+        .is_none_or(|middleIterator| middleIterator.size_hint( ).1 == Some(0))};
+
+    let (frontLower, frontUpper)= innerSizeHint(&self.frontInnerIterator);
+    let (backLower, backUpper)= innerSizeHint(&self.backInnerIterator);
+
+    let lower= frontLower.saturating_add(backLower);
+
+    let nothingElseLeft= self.outerIterator.size_hint( ).1 == Some(0)
+      && isEmpty(&self.frontMiddleIterator)
+      && isEmpty(&self.backMiddleIterator);
+
+    match (nothingElseLeft, frontUpper, backUpper) {
+      (true, Some(frontUpper), Some(backUpper)) => (lower, frontUpper.checked_add(backUpper)),
+      _ => (lower, None)
+    }
+  }
+}
+
+impl<O> DoubleEndedIterator for FlattenDeep2<O>
+  where O: DoubleEndedIterator,
+        O::Item: IntoIterator,
+        <O::Item as IntoIterator>::Item: IntoIterator,
+        MiddleIterator<O>: DoubleEndedIterator,
+        InnerIterator<O>: DoubleEndedIterator
+{
+  fn next_back(&mut self) -> Option<Self::Item> {
+    loop {
+      if let Some(ref mut innerIterator)= self.backInnerIterator {
+        let nextItem= innerIterator.next_back( );
+
+        if nextItem.is_some( ) {
+          return nextItem}
+
+        self.backInnerIterator= None;
+      }
+
+      if let Some(ref mut middleIterator)= self.backMiddleIterator {
+        if let Some(nextInnerIterator)= middleIterator.next_back( ) {
+          self.backInnerIterator= Some(nextInnerIterator.into_iter( ));
+          continue;
+        }
+
+        self.backMiddleIterator= None;
+      }
+
+      if let Some(nextMiddleIterator)= self.outerIterator.next_back( ) {
+        self.backMiddleIterator= Some(nextMiddleIterator.into_iter( ));
+        continue;
+      }
+
+      if let Some(ref mut middleIterator)= self.frontMiddleIterator {
+        if let Some(nextInnerIterator)= middleIterator.next_back( ) {
+          self.backInnerIterator= Some(nextInnerIterator.into_iter( ));
+          continue;
+        }
+
+        self.frontMiddleIterator= None;
+      }
+
+      let nextItem= self.frontInnerIterator.as_mut( )?.next_back( );
+
+      if nextItem.is_none( ) {
+        self.frontInnerIterator= None;
+      }
+      return nextItem;
+    }
+  }
+}
+
+// Same as for Flatten : exhausted iterators are dropped right away, and the outer one is fused.
+impl<O> FusedIterator for FlattenDeep2<O>
+  where O: Iterator,
+        O::Item: IntoIterator,
+        <O::Item as IntoIterator>::Item: IntoIterator
+{ }
+
+impl<O> Clone for FlattenDeep2<O>
+  where O: Iterator + Clone,
+        O::Item: IntoIterator,
+        <O::Item as IntoIterator>::Item: IntoIterator,
+        MiddleIterator<O>: Clone,
+        InnerIterator<O>: Clone
+{
+  fn clone(&self) -> Self {
+    Self {
+      outerIterator: self.outerIterator.clone( ),
+      frontMiddleIterator: self.frontMiddleIterator.clone( ),
+      frontInnerIterator: self.frontInnerIterator.clone( ),
+      backMiddleIterator: self.backMiddleIterator.clone( ),
+      backInnerIterator: self.backInnerIterator.clone( )
+    }
+  }
+}
+
+impl<O> fmt::Debug for FlattenDeep2<O>
+  where O: Iterator + fmt::Debug,
+        O::Item: IntoIterator,
+        <O::Item as IntoIterator>::Item: IntoIterator,
+        MiddleIterator<O>: fmt::Debug,
+        InnerIterator<O>: fmt::Debug
+{
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_struct("FlattenDeep2")
+      .field("outerIterator", &self.outerIterator)
+      .field("frontMiddleIterator", &self.frontMiddleIterator)
+      .field("frontInnerIterator", &self.frontInnerIterator)
+      .field("backMiddleIterator", &self.backMiddleIterator)
+      .field("backInnerIterator", &self.backInnerIterator)
+      .finish( )
+  }
+}
+
+//---
+
+#[derive(Clone, Debug)]
+pub struct CycleN<I> {
+  // A fresh copy of the iterator, which gets cloned at the start of each round.
+  original: I,
+  current: I,
+
+  // Number of rounds left, including the current one.
+  rounds: usize,
+
+  // Whether the current round has yielded any item. If a round ends without yielding anything,
+  // the iterator is empty and the rest of the rounds are skipped (instead of going through them
+  // one by one).
+  yieldedInRound: bool
+}
+
+impl<I> CycleN<I>
+  where I: Iterator + Clone
+{
+  pub fn new(iterator: I, times: usize) -> Self {
+    Self {
+      original: iterator.clone( ),
+      current: iterator,
+      rounds: times,
+      yieldedInRound: false
+    }
+  }
+}
+
+impl<I> Iterator for CycleN<I>
+  where I: Iterator + Clone
+{
+  type Item = I::Item;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    while self.rounds > 0 {
+      if let Some(item)= self.current.next( ) {
+        self.yieldedInRound= true;
+        return Some(item);
+      }
+
+      if !self.yieldedInRound {
+        self.rounds= 0;
+        break;
+      }
+
+      self.rounds -= 1;
+      if self.rounds > 0 {
+        self.current= self.original.clone( );
+        self.yieldedInRound= false;
+      }
+    }
+    None
+  }
+
+  // Whatever's left in the current round, plus the length of the original iterator for each of
+  // the remaining rounds.
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    if self.rounds == 0 {
+      return (0, Some(0))}
+
+    let (currentLower, currentUpper)= self.current.size_hint( );
+    let (originalLower, originalUpper)= self.original.size_hint( );
+    let remainingRounds= self.rounds - 1;
+
+    let lower= originalLower.saturating_mul(remainingRounds).saturating_add(currentLower);
+    let upper= originalUpper
+      .and_then(|originalUpper| originalUpper.checked_mul(remainingRounds))
+      .zip(currentUpper)
+      .and_then(|(upper, currentUpper)| upper.checked_add(currentUpper));
+
+    (lower, upper)
+  }
+}
+
+// NOTE - len panics if the total length doesn't fit in a usize.
+impl<I> ExactSizeIterator for CycleN<I>
+  where I: ExactSizeIterator + Clone
+{ }
+
+impl<I> FusedIterator for CycleN<I>
+  where I: Iterator + Clone
+{ }
+
+// Unlike std's Cycle, the clone of the original iterator is taken only when the current one is
+// exhausted (and not after each item). Returns None only if the iterator is empty.
+#[derive(Clone, Debug)]
+pub struct Cycle<I> {
+  original: I,
+  current: I
+}
+
+impl<I> Iterator for Cycle<I>
+  where I: Iterator + Clone
+{
+  type Item = I::Item;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    match self.current.next( ) {
+      None => {
+        self.current= self.original.clone( );
+        self.current.next( )
+      },
+
+      item => item
+    }
+  }
+
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    match self.original.size_hint( ) {
+      (0, Some(0)) => (0, Some(0)),
+
+      // The iterator might turn out to be empty.
+      (0, _) => (0, None),
+
+      _ => (usize::MAX, None)
+    }
+  }
+}
+
+//---
+
+#[derive(Clone)]
+pub struct InspectIndexed<I, F> {
+  iterator: I,
+
+  // Index of the next item from the front.
+  index: usize,
+
+  f: F
+}
+
+impl<I, F> Iterator for InspectIndexed<I, F>
+  where I: Iterator,
+        F: FnMut(usize, &I::Item)
+{
+  type Item = I::Item;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    let item= self.iterator.next( )?;
+
+    (self.f)(self.index, &item);
+    self.index += 1;
+
+    Some(item)
+  }
+
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    self.iterator.size_hint( )
+  }
+}
+
+impl<I, F> DoubleEndedIterator for InspectIndexed<I, F>
+  where I: DoubleEndedIterator + ExactSizeIterator,
+        F: FnMut(usize, &I::Item)
+{
+  // Whatever's left after taking out the item from the back, lies between it and the front.
+  fn next_back(&mut self) -> Option<Self::Item> {
+    let item= self.iterator.next_back( )?;
+
+    (self.f)(self.index + self.iterator.len( ), &item);
+
+    Some(item)
+  }
+}
+
+impl<I, F> ExactSizeIterator for InspectIndexed<I, F>
+  where I: ExactSizeIterator,
+        F: FnMut(usize, &I::Item)
+{ }
+
+impl<I, F> FusedIterator for InspectIndexed<I, F>
+  where I: FusedIterator,
+        F: FnMut(usize, &I::Item)
+{ }
+
+// f is left out, since closures aren't Debug.
+impl<I, F> fmt::Debug for InspectIndexed<I, F>
+  where I: fmt::Debug
+{
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_struct("InspectIndexed")
+      .field("iterator", &self.iterator)
+      .field("index", &self.index)
+      .finish_non_exhaustive( )
+  }
+}
+
+//---
+
+#[derive(Clone, Debug)]
+pub struct EnumerateFrom<I, N> {
+  iterator: I,
+
+  // Count of the next item from the front.
+  next: N
+}
+
+impl<I, N> EnumerateFrom<I, N>
+  where N: TryFrom<usize> + AddAssign + Clone
+{
+  // Count of the item which is offset items after the next one from the front.
+  fn countAt(&self, offset: usize) -> N {
+    let mut count= self.next.clone( );
+    count += N::try_from(offset).unwrap_or_else(|_| panic!("count doesn't fit"));
+    count
+  }
+}
+
+impl<I, N> Iterator for EnumerateFrom<I, N>
+  where I: Iterator,
+        N: TryFrom<usize> + AddAssign + Clone
+{
+  type Item = (N, I::Item);
+
+  fn next(&mut self) -> Option<Self::Item> {
+    let item= self.iterator.next( )?;
+
+    let count= self.next.clone( );
+    self.next= self.countAt(1);
+
+    Some((count, item))
+  }
+
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    self.iterator.size_hint( )
+  }
+}
+
+impl<I, N> DoubleEndedIterator for EnumerateFrom<I, N>
+  where I: DoubleEndedIterator + ExactSizeIterator,
+        N: TryFrom<usize> + AddAssign + Clone
+{
+  fn next_back(&mut self) -> Option<Self::Item> {
+    let item= self.iterator.next_back( )?;
+    Some((self.countAt(self.iterator.len( )), item))
+  }
+}
+
+impl<I, N> ExactSizeIterator for EnumerateFrom<I, N>
+  where I: ExactSizeIterator,
+        N: TryFrom<usize> + AddAssign + Clone
+{ }
+
+impl<I, N> FusedIterator for EnumerateFrom<I, N>
+  where I: FusedIterator,
+        N: TryFrom<usize> + AddAssign + Clone
+{ }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn case_FlattenSizeHint( ) {
+    let mut iterator= flatten(vec![ vec![ 1, 2 ], vec![ 3, 4, 5 ], vec![ 6 ] ]);
+    assert_eq!((0, None), iterator.size_hint( ));
+
+    assert_eq!(Some(1), iterator.next( ));
+    assert_eq!((1, None), iterator.size_hint( ));
+
+    assert_eq!(Some(6), iterator.next_back( ));
+    assert_eq!((1, None), iterator.size_hint( ));
+
+    assert_eq!(Some(2), iterator.next( ));
+    assert_eq!(Some(3), iterator.next( ));
+
+    // The outer iterator is exhausted now. So, the hint is exact.
+    assert_eq!((2, Some(2)), iterator.size_hint( ));
+
+    assert_eq!(Some(5), iterator.next_back( ));
+    assert_eq!((1, Some(1)), iterator.size_hint( ));
+
+    assert_eq!(Some(4), iterator.next( ));
+    assert_eq!((0, Some(0)), iterator.size_hint( ));
+    assert_eq!(None, iterator.next( ));
+  }
+
+  #[test]
+  fn case_FlattenCount( ) {
+    let nested= vec![ vec![ 1, 2 ], vec![ ], vec![ 3, 4, 5 ], vec![ 6 ] ];
+    assert_eq!(6, flatten(nested.clone( )).count( ));
+
+    let mut iterator= flatten(nested);
+    iterator.next( );
+    iterator.next_back( );
+    assert_eq!(4, iterator.count( ));
+  }
+
+  #[test]
+  fn case_FlattenIntoIteratorOuters( ) {
+    let nested= vec![ vec![ 1, 2 ], vec![ ], vec![ 3 ] ];
+    assert_eq!(vec![ 1, 2, 3 ], flatten(nested.clone( )).collect::<Vec<_>>( ));
+    assert_eq!(vec![ 3, 2, 1 ], flatten(nested).rev( ).collect::<Vec<_>>( ));
+
+    let arrays= [ [ 1, 2 ], [ 3, 4 ] ];
+    assert_eq!(vec![ 1, 2, 3, 4 ], flatten(arrays).collect::<Vec<_>>( ));
+    assert_eq!(vec![ 4, 3, 2, 1 ], flatten(arrays).rev( ).collect::<Vec<_>>( ));
+
+    // Iterating over references to the inner Vecs.
+    let map= std::collections::HashMap::from([ ("a", vec![ 1, 2 ]), ("b", vec![ 3 ]) ]);
+
+    let mut forwards: Vec<_>= flatten(map.values( )).copied( ).collect( );
+    forwards.sort( );
+    assert_eq!(vec![ 1, 2, 3 ], forwards);
+
+    // HashMap's values can't be iterated backwards. But BTreeMap's can.
+    let map= std::collections::BTreeMap::from([ ("a", vec![ 1, 2 ]), ("b", vec![ 3 ]) ]);
+    assert_eq!(vec![ 3, 2, 1 ], flatten(map.values( )).rev( ).copied( ).collect::<Vec<_>>( ));
+  }
+
+  // Alternates between returning None and Some, from both the ends.
+  struct NonFused {
+    calls: usize
+  }
+
+  impl Iterator for NonFused {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+      self.calls += 1;
+      self.calls.is_multiple_of(2).then_some(self.calls)
+    }
+  }
+
+  impl DoubleEndedIterator for NonFused {
+    fn next_back(&mut self) -> Option<usize> {
+      self.next( )}
+  }
+
+  #[test]
+  fn case_FlattenStaysExhausted( ) {
+    // Non-fused outer iterator.
+    let mut iterator= flatten(NonFused { calls: 0 }.map(|n| vec![ n ]));
+    assert_eq!(None, iterator.next( ));
+    assert_eq!(None, iterator.next( ));
+    assert_eq!(None, iterator.next_back( ));
+
+    let mut iterator= flatten(NonFused { calls: 0 }.map(|n| vec![ n ]));
+    assert_eq!(None, iterator.next_back( ));
+    assert_eq!(None, iterator.next_back( ));
+    assert_eq!(None, iterator.next( ));
+
+    // Non-fused inner iterators, left over by the other end.
+    let mut iterator= flatten(vec![ NonFused { calls: 1 } ]);
+    assert_eq!(Some(2), iterator.next_back( ));
+    assert_eq!(None, iterator.next( ));
+    assert_eq!(None, iterator.next( ));
+    assert_eq!(None, iterator.next_back( ));
+
+    let mut iterator= flatten(vec![ NonFused { calls: 1 } ]);
+    assert_eq!(Some(2), iterator.next( ));
+    assert_eq!(None, iterator.next_back( ));
+    assert_eq!(None, iterator.next_back( ));
+    assert_eq!(None, iterator.next( ));
+  }
+
+  #[test]
+  fn case_IteratorExtOnAdapters( ) {
+    let mapped= (1..4).map(|i| vec![ i; i ]);
+    assert_eq!(vec![ 1, 2, 2, 3, 3, 3 ], mapped.flatten_ext( ).collect::<Vec<_>>( ));
+
+    let filtered= vec![ vec![ 1 ], vec![ ], vec![ 2, 3 ] ].into_iter( ).filter(|v| v.len( ) != 1);
+    assert_eq!(vec![ 3, 2 ], filtered.flatten_ext( ).rev( ).collect::<Vec<_>>( ));
+  }
+
+  #[test]
+  fn case_FlatMapWordsToChars( ) {
+    let words= "ab c def";
+
+    assert_eq!("abcdef", flat_map(words.split(' '), str::chars).collect::<String>( ));
+    assert_eq!("fedcba", flat_map(words.split(' '), str::chars).rev( ).collect::<String>( ));
+
+    let mut iterator= words.split(' ').flat_map_ext(str::chars);
+    assert_eq!(Some('a'), iterator.next( ));
+    assert_eq!(Some('f'), iterator.next_back( ));
+    assert_eq!("bcde", iterator.collect::<String>( ));
+  }
+
+  #[test]
+  fn case_FlatMapIsLazy( ) {
+    let calls= std::cell::Cell::new(0);
+
+    let mut iterator= flat_map(vec![ 1, 2, 3 ], |n| {
+      calls.set(calls.get( ) + 1);
+      vec![ n; n ]
+    });
+    assert_eq!(0, calls.get( ));
+    assert_eq!((0, None), iterator.size_hint( ));
+
+    assert_eq!(Some(1), iterator.next( ));
+    assert_eq!(1, calls.get( ));
+
+    assert_eq!(Some(3), iterator.next_back( ));
+    assert_eq!(2, calls.get( ));
+    assert_eq!((2, None), iterator.size_hint( ));
+
+    assert_eq!(Some(2), iterator.next( ));
+    assert_eq!(3, calls.get( ));
+    assert_eq!((3, Some(3)), iterator.size_hint( ));
+
+    assert_eq!(vec![ 2, 3, 3 ], iterator.collect::<Vec<_>>( ));
+    assert_eq!(3, calls.get( ));
+  }
+
+  #[test]
+  fn case_Interleave( ) {
+    // Equal lengths.
+    assert_eq!(vec![ 1, 10, 2, 20 ], interleave(vec![ 1, 2 ], vec![ 10, 20 ]).collect::<Vec<_>>( ));
+
+    // Unequal lengths.
+    let iterator= (1..5).interleave(vec![ 10 ]);
+    assert_eq!((5, Some(5)), iterator.size_hint( ));
+    assert_eq!(vec![ 1, 10, 2, 3, 4 ], iterator.collect::<Vec<_>>( ));
+
+    assert_eq!(vec![ 1, 10, 20, 30 ], (1..2).interleave(vec![ 10, 20, 30 ]).collect::<Vec<_>>( ));
+
+    // One empty.
+    assert_eq!(vec![ 1, 2 ], (1..3).interleave(vec![ ]).collect::<Vec<_>>( ));
+    assert_eq!(vec![ 10, 20 ], (1..1).interleave(vec![ 10, 20 ]).collect::<Vec<_>>( ));
+  }
+
+  #[test]
+  fn case_InterleaveBackwards( ) {
+    for aLength in 0..5 {
+      for bLength in 0..5 {
+        let a= 0..aLength;
+        let b= 10..(10 + bLength);
+
+        let mut forwards: Vec<_>= interleave(a.clone( ), b.clone( )).collect( );
+        assert_eq!(aLength + bLength, forwards.len( ));
+
+        let backwards: Vec<_>= interleave(a.clone( ), b.clone( )).rev( ).collect( );
+        forwards.reverse( );
+        assert_eq!(forwards, backwards);
+
+        // Alternating between both the ends.
+        forwards.reverse( );
+        let mut iterator= interleave(a, b);
+        let (mut front, mut back)= (Vec::new( ), Vec::new( ));
+        while let Some(item)= iterator.next( ) {
+          front.push(item);
+
+          match iterator.next_back( ) {
+            Some(item) => back.push(item),
+            None => break
+          }
+        }
+        back.reverse( );
+        front.extend(back);
+        assert_eq!(forwards, front);
+      }
+    }
+  }
+
+  #[test]
+  fn case_Chunks( ) {
+    // Exact multiple.
+    let chunks= (0..6).chunks_ext(2);
+    assert_eq!(3, chunks.len( ));
+    assert_eq!(vec![ vec![ 0, 1 ], vec![ 2, 3 ], vec![ 4, 5 ] ], chunks.collect::<Vec<_>>( ));
+
+    // With a shorter last chunk.
+    let chunks= (0..7).chunks_ext(3);
+    assert_eq!(3, chunks.len( ));
+    assert_eq!(vec![ vec![ 0, 1, 2 ], vec![ 3, 4, 5 ], vec![ 6 ] ], chunks.collect::<Vec<_>>( ));
+
+    // Size larger than the number of items.
+    assert_eq!(vec![ vec![ 0, 1 ] ], (0..2).chunks_ext(5).collect::<Vec<_>>( ));
+    assert_eq!(0, (0..0).chunks_ext(5).count( ));
+
+    assert_eq!(vec![ vec![ 0 ], vec![ 1 ] ], (0..2).chunks_ext(1).collect::<Vec<_>>( ));
+  }
+
+  #[test]
+  #[should_panic(expected = "chunk size must be non-zero")]
+  fn case_ChunksOfZero( ) {
+    (0..2).chunks_ext(0);
+  }
+
+  #[test]
+  fn case_ChunksIsLazy( ) {
+    let pulled= std::cell::Cell::new(0);
+
+    let mut chunks= (0..10).inspect(|_| pulled.set(pulled.get( ) + 1)).chunks_ext(4);
+    assert_eq!(0, pulled.get( ));
+
+    assert_eq!(Some(vec![ 0, 1, 2, 3 ]), chunks.next( ));
+    assert_eq!(4, pulled.get( ));
+  }
+
+  #[test]
+  fn case_WindowsMovingSum( ) {
+    let windows= (1..=5).windows_ext(3);
+    assert_eq!((3, Some(3)), windows.size_hint( ));
+
+    let sums: Vec<i32>= windows.map(|window| window.iter( ).sum( )).collect( );
+    assert_eq!(vec![ 6, 9, 12 ], sums);
+
+    let mut windows= (1..=5).windows_ext(1);
+    assert_eq!(Some(vec![ 1 ]), windows.next( ));
+    assert_eq!((4, Some(4)), windows.size_hint( ));
+  }
+
+  #[test]
+  fn case_WindowsOfShortStream( ) {
+    let mut windows= (1..3).windows_ext(3);
+    assert_eq!((0, Some(0)), windows.size_hint( ));
+    assert_eq!(None, windows.next( ));
+    assert_eq!(None, windows.next( ));
+  }
+
+  #[test]
+  fn case_WindowsCloneOncePerAdvance( ) {
+    struct Counted(std::rc::Rc<std::cell::Cell<usize>>);
+
+    impl Clone for Counted {
+      fn clone(&self) -> Self {
+        self.0.set(self.0.get( ) + 1);
+        Counted(self.0.clone( ))
+      }
+    }
+
+    let clones= std::rc::Rc::new(std::cell::Cell::new(0));
+
+    let mut windows= (0..5).map(|_| Counted(clones.clone( ))).windows_ext(3);
+    for windowCount in 1..=3 {
+      assert!(windows.next( ).is_some( ));
+      assert_eq!(windowCount * 3, clones.get( ));
+    }
+    assert!(windows.next( ).is_none( ));
+    assert_eq!(9, clones.get( ));
+  }
+
+  #[test]
+  fn case_ZipLongest( ) {
+    use EitherOrBoth::*;
+
+    let zipped: Vec<_>= (0..2).zip_longest_ext(vec![ 'a', 'b' ]).collect( );
+    assert_eq!(vec![ Both(0, 'a'), Both(1, 'b') ], zipped);
+    assert!(zipped.iter( ).all(EitherOrBoth::is_both));
+
+    let zipped= (0..4).zip_longest_ext(vec![ 'a', 'b' ]);
+    assert_eq!(4, zipped.len( ));
+    assert_eq!(
+      vec![ Both(0, 'a'), Both(1, 'b'), Left(2), Left(3) ],
+      zipped.collect::<Vec<_>>( )
+    );
+
+    let zipped= (0..1).zip_longest_ext(vec![ 'a', 'b' ]);
+    assert_eq!(vec![ Both(0, 'a'), Right('b') ], zipped.collect::<Vec<_>>( ));
+  }
+
+  #[test]
+  fn case_ZipLongestBackwards( ) {
+    for aLength in 0..4 {
+      for bLength in 0..4 {
+        let zipped= || (0..aLength).zip_longest_ext(10..(10 + bLength));
+
+        let mut forwards: Vec<_>= zipped( ).collect( );
+        forwards.reverse( );
+        assert_eq!(forwards, zipped( ).rev( ).collect::<Vec<_>>( ));
+      }
+    }
+
+    let mut zipped= (0..3).zip_longest_ext(vec![ 'a' ]);
+    assert_eq!(Some(EitherOrBoth::Left(2)), zipped.next_back( ));
+    assert_eq!(Some(EitherOrBoth::Both(0, 'a')), zipped.next( ));
+    assert_eq!(Some(EitherOrBoth::Left(1)), zipped.next_back( ));
+    assert_eq!(None, zipped.next( ));
+  }
+
+  #[test]
+  fn case_EitherOrBothHelpers( ) {
+    let both: EitherOrBoth<i32, &str>= EitherOrBoth::Both(1, "a");
+    let left: EitherOrBoth<i32, &str>= EitherOrBoth::Left(2);
+    let right: EitherOrBoth<i32, &str>= EitherOrBoth::Right("b");
+
+    assert_eq!((Some(1), Some("a")), (both.left( ), both.right( )));
+    assert_eq!((Some(2), None), (left.left( ), left.right( )));
+    assert_eq!((None, Some("b")), (right.left( ), right.right( )));
+
+    assert_eq!((1, "a"), both.or_default( ));
+    assert_eq!((2, ""), left.or_default( ));
+    assert_eq!((0, "b"), right.or_default( ));
+
+    assert_eq!((2, "z"), left.or(9, "z"));
+    assert_eq!((9, "b"), right.or(9, "z"));
+  }
+
+  #[test]
+  fn case_CloneMidIteration( ) {
+    let mut iterator= flatten(vec![ vec![ 1, 2, 3 ], vec![ 4 ], vec![ 5, 6 ] ]);
+    assert_eq!(Some(1), iterator.next( ));
+    assert_eq!(Some(6), iterator.next_back( ));
+
+    let forked= iterator.clone( );
+    assert_eq!(vec![ 2, 3, 4, 5 ], iterator.collect::<Vec<_>>( ));
+    assert_eq!(vec![ 5, 4, 3, 2 ], forked.rev( ).collect::<Vec<_>>( ));
+
+    let mut iterator= flat_map(vec![ "ab", "cd" ], str::chars);
+    iterator.next( );
+    assert_eq!("bcd", iterator.clone( ).collect::<String>( ));
+    assert_eq!("bcd", iterator.collect::<String>( ));
+
+    let mut windows= (0..4).windows_ext(2);
+    windows.next( );
+    assert_eq!(windows.clone( ).collect::<Vec<_>>( ), windows.collect::<Vec<_>>( ));
+  }
+
+  #[test]
+  fn case_Debug( ) {
+    let iterator= flatten(vec![ vec![ 1 ] ]);
+    assert!(format!("{:?}", iterator).starts_with("Flatten { outerIterator: "));
+
+    let iterator= flat_map(vec![ 1 ], |n| vec![ n ]);
+    assert!(format!("{:?}", iterator).ends_with(", .. }"));
+  }
+
+  #[test]
+  fn case_Intersperse( ) {
+    let tokens= vec![ "a", "b", "c" ].into_iter( ).intersperse_ext(", ");
+    assert_eq!((5, Some(5)), tokens.size_hint( ));
+    assert_eq!("a, b, c", tokens.collect::<String>( ));
+
+    assert_eq!("a", vec![ "a" ].into_iter( ).intersperse_ext(", ").collect::<String>( ));
+
+    let mut empty= std::iter::empty::<&str>( ).intersperse_ext(", ");
+    assert_eq!((0, Some(0)), empty.size_hint( ));
+    assert_eq!(None, empty.next( ));
+
+    // Midway, right after yielding a separator.
+    let mut tokens= (1..4).intersperse_ext(0);
+    assert_eq!(Some(1), tokens.next( ));
+    assert_eq!((4, Some(4)), tokens.size_hint( ));
+    assert_eq!(Some(0), tokens.next( ));
+    assert_eq!((3, Some(3)), tokens.size_hint( ));
+    assert_eq!(vec![ 2, 0, 3 ], tokens.collect::<Vec<_>>( ));
+  }
+
+  #[test]
+  fn case_IntersperseWithIsLazy( ) {
+    let calls= std::cell::Cell::new(0);
+
+    let tokens= vec![ String::from("a"), String::from("b") ];
+    let mut tokens= tokens.into_iter( ).intersperse_with_ext(|| {
+      calls.set(calls.get( ) + 1);
+      String::from("-")
+    });
+    assert_eq!(0, calls.get( ));
+
+    assert_eq!(Some(String::from("a")), tokens.next( ));
+    assert_eq!(0, calls.get( ));
+
+    assert_eq!("-b", tokens.collect::<String>( ));
+    assert_eq!(1, calls.get( ));
+  }
+
+  #[test]
+  fn case_ChunkBy( ) {
+    let mut groups= std::iter::empty::<i32>( ).chunk_by_ext(|n| n % 2);
+    assert_eq!((0, Some(0)), groups.size_hint( ));
+    assert_eq!(None, groups.next( ));
+
+    // All in one group.
+    let groups: Vec<_>= vec![ 2, 4, 6 ].into_iter( ).chunk_by_ext(|n| n % 2).collect( );
+    assert_eq!(vec![ (0, vec![ 2, 4, 6 ]) ], groups);
+
+    // Alternating keys.
+    let groups: Vec<_>= (1..=3).chunk_by_ext(|n| n % 2).collect( );
+    assert_eq!(vec![ (1, vec![ 1 ]), (0, vec![ 2 ]), (1, vec![ 3 ]) ], groups);
+
+    let words= vec![ "apple", "avocado", "banana", "blueberry", "cherry", "apricot" ];
+    let groups: Vec<_>= words.into_iter( ).chunk_by_ext(|word| word.chars( ).next( )).collect( );
+    assert_eq!(
+      vec![
+        (Some('a'), vec![ "apple", "avocado" ]),
+        (Some('b'), vec![ "banana", "blueberry" ]),
+        (Some('c'), vec![ "cherry" ]),
+        (Some('a'), vec![ "apricot" ])
+      ],
+      groups
+    );
+  }
+
+  #[test]
+  fn case_ChunkByCallsKeyFnOncePerItem( ) {
+    let calls= std::cell::Cell::new(0);
+
+    let groups= vec![ 1, 1, 2, 3, 3, 3 ].into_iter( ).chunk_by_ext(|&n| {
+      calls.set(calls.get( ) + 1);
+      n
+    });
+    assert_eq!(3, groups.count( ));
+    assert_eq!(6, calls.get( ));
+  }
+
+  #[test]
+  fn case_Merge( ) {
+    let merged= (0..10).step_by(2).merge((1..10).step_by(3));
+    assert_eq!((8, Some(8)), merged.size_hint( ));
+    assert_eq!(vec![ 0, 1, 2, 4, 4, 6, 7, 8 ], merged.collect::<Vec<_>>( ));
+
+    assert_eq!(vec![ 1, 2 ], (1..3).merge(vec![ ]).collect::<Vec<_>>( ));
+    assert_eq!(vec![ 1, 2 ], std::iter::empty( ).merge(1..3).collect::<Vec<_>>( ));
+  }
+
+  #[test]
+  fn case_MergeByIsStable( ) {
+    let a= vec![ (1, 'a'), (2, 'a'), (2, 'a') ];
+    let b= vec![ (1, 'b'), (2, 'b'), (3, 'b') ];
+
+    let merged: Vec<_>= a.into_iter( ).merge_by(b, |x, y| x.0.cmp(&y.0)).collect( );
+    assert_eq!(
+      vec![ (1, 'a'), (1, 'b'), (2, 'a'), (2, 'a'), (2, 'b'), (3, 'b') ],
+      merged
+    );
+
+    // Sorted in descending order.
+    let merged= vec![ 5, 3 ].into_iter( ).merge_by(vec![ 4, 1 ], |x, y| y.cmp(x));
+    assert_eq!(vec![ 5, 4, 3, 1 ], merged.collect::<Vec<_>>( ));
+  }
+
+  // Deterministic pseudo random numbers (xorshift), shared by the randomized tests.
+  fn xorshift(state: &mut u64) -> u64 {
+    *state ^= *state << 13;
+    *state ^= *state >> 7;
+    *state ^= *state << 17;
+    *state
+  }
+
+  #[test]
+  fn case_MergeRandomSortedVecs( ) {
+    let mut state: u64= 0x2545F4914F6CDD1D;
+
+    for _ in 0..100 {
+      let mut a: Vec<_>= (0..xorshift(&mut state) % 20)
+        .map(|_| xorshift(&mut state) % 50)
+        .collect( );
+      let mut b: Vec<_>= (0..xorshift(&mut state) % 20)
+        .map(|_| xorshift(&mut state) % 50)
+        .collect( );
+      a.sort( );
+      b.sort( );
+
+      let mut expected= [ a.clone( ), b.clone( ) ].concat( );
+      expected.sort( );
+
+      assert_eq!(expected, a.into_iter( ).merge(b).collect::<Vec<_>>( ));
+    }
+  }
+
+  #[test]
+  fn case_MultiPeek( ) {
+    // Peek 3 ahead, then consume.
+    let mut iterator= (1..6).multipeek_ext( );
+    assert_eq!(Some(&1), iterator.peek( ));
+    assert_eq!(Some(&2), iterator.peek( ));
+    assert_eq!(Some(&3), iterator.peek( ));
+    assert_eq!((5, Some(5)), iterator.size_hint( ));
+    assert_eq!(vec![ 1, 2, 3, 4, 5 ], iterator.collect::<Vec<_>>( ));
+
+    // Peeking beyond the end.
+    let mut iterator= (1..3).multipeek_ext( );
+    assert_eq!(Some(&1), iterator.peek( ));
+    assert_eq!(Some(&2), iterator.peek( ));
+    assert_eq!(None, iterator.peek( ));
+    assert_eq!(None, iterator.peek( ));
+    assert_eq!(vec![ 1, 2 ], iterator.collect::<Vec<_>>( ));
+  }
+
+  #[test]
+  fn case_MultiPeekReset( ) {
+    let mut iterator= (1..6).multipeek_ext( );
+    assert_eq!(Some(&1), iterator.peek( ));
+    assert_eq!(Some(&2), iterator.peek( ));
+
+    iterator.reset_peek( );
+    assert_eq!(Some(&1), iterator.peek( ));
+
+    // next resets the lookahead as well.
+    assert_eq!(Some(1), iterator.next( ));
+    assert_eq!(Some(&2), iterator.peek( ));
+    assert_eq!(Some(&3), iterator.peek( ));
+    assert_eq!(Some(&4), iterator.peek( ));
+
+    assert_eq!(Some(2), iterator.next( ));
+    assert_eq!(Some(&3), iterator.peek( ));
+    assert_eq!(Some(3), iterator.next( ));
+    assert_eq!(Some(4), iterator.next( ));
+    assert_eq!(Some(&5), iterator.peek( ));
+    assert_eq!(None, iterator.peek( ));
+    assert_eq!(Some(5), iterator.next( ));
+    assert_eq!(None, iterator.next( ));
+  }
+
+  // Consumes the iterator from both the ends, as per the pattern (bit i set means the ith step is
+  // a next_back). Returns the items as they'd be in forwards order.
+  fn consumeFromBothEnds<I>(mut iterator: I, pattern: u32) -> Vec<I::Item>
+    where I: DoubleEndedIterator
+  {
+    let (mut front, mut back)= (Vec::new( ), Vec::new( ));
+
+    for step in 0.. {
+      let fromBack= step < 32 && pattern & (1 << step) != 0;
+
+      let item= if fromBack { iterator.next_back( ) } else { iterator.next( ) };
+      match item {
+        Some(item) if fromBack => back.push(item),
+        Some(item) => front.push(item),
+        None => break
+      }
+    }
+
+    // Stays exhausted from both the ends.
+    assert!(iterator.next( ).is_none( ) && iterator.next_back( ).is_none( ));
+
+    back.reverse( );
+    front.extend(back);
+    front
+  }
+
+  #[test]
+  fn case_FlattenMeetInTheMiddle( ) {
+    let nested= vec![ vec![ 1, 2, 3 ], vec![ 4 ], vec![ 5, 6 ] ];
+
+    // Every interleaving of next and next_back.
+    for pattern in 0..(1 << 7) {
+      assert_eq!(vec![ 1, 2, 3, 4, 5, 6 ], consumeFromBothEnds(flatten(nested.clone( )), pattern));
+    }
+
+    // With empty inner iterators in between.
+    let nested= vec![ vec![ ], vec![ 1, 2 ], vec![ ], vec![ 3 ], vec![ ] ];
+    for pattern in 0..(1 << 4) {
+      assert_eq!(vec![ 1, 2, 3 ], consumeFromBothEnds(flatten(nested.clone( )), pattern));
+    }
+  }
+
+  #[test]
+  fn case_FlattenBothEndsInSameInnerIterator( ) {
+    let mut iterator= flatten(vec![ vec![ 1, 2, 3, 4 ] ]);
+    assert_eq!(Some(1), iterator.next( ));
+    assert_eq!(Some(4), iterator.next_back( ));
+    assert_eq!(Some(2), iterator.next( ));
+    assert_eq!(Some(3), iterator.next_back( ));
+    assert_eq!(None, iterator.next( ));
+    assert_eq!(None, iterator.next_back( ));
+
+    // The outer iterator gets exhausted by next_back, while next is midway through the first inner
+    // iterator.
+    let mut iterator= flatten(vec![ vec![ 1, 2, 3 ], vec![ 4, 5 ] ]);
+    assert_eq!(Some(1), iterator.next( ));
+    assert_eq!(Some(5), iterator.next_back( ));
+    assert_eq!(Some(4), iterator.next_back( ));
+    assert_eq!(Some(3), iterator.next_back( ));
+    assert_eq!(Some(2), iterator.next( ));
+    assert_eq!(None, iterator.next_back( ));
+    assert_eq!(None, iterator.next( ));
+  }
+
+  #[test]
+  fn case_TakeWhileInclusive( ) {
+    // Terminator in the middle.
+    let mut iterator= vec![ 1, 2, 0, 3 ].into_iter( ).take_while_inclusive_ext(|&n| n != 0);
+    assert_eq!(vec![ 1, 2, 0 ], iterator.by_ref( ).collect::<Vec<_>>( ));
+    assert_eq!((0, Some(0)), iterator.size_hint( ));
+    assert_eq!(None, iterator.next( ));
+
+    // Predicate holds for everything.
+    assert_eq!(vec![ 1, 2 ], (1..3).take_while_inclusive_ext(|_| true).collect::<Vec<_>>( ));
+
+    assert_eq!(None, (1..1).take_while_inclusive_ext(|_| true).next( ));
+  }
+
+  #[test]
+  fn case_TakeWhileInclusiveOverFlatten( ) {
+    let lines= vec![ vec![ "GET", "/" ], vec![ "Host:", "x", ";" ], vec![ "body" ] ];
+
+    let header: Vec<_>= flatten(lines).take_while_inclusive_ext(|&token| token != ";").collect( );
+    assert_eq!(vec![ "GET", "/", "Host:", "x", ";" ], header);
+  }
+
+  #[test]
+  fn case_Unique( ) {
+    let iterator= vec![ 3, 1, 3, 2, 1, 4, 2 ].into_iter( ).unique_ext( );
+    assert_eq!((1, Some(7)), iterator.size_hint( ));
+    assert_eq!(vec![ 3, 1, 2, 4 ], iterator.collect::<Vec<_>>( ));
+
+    let mut iterator= std::iter::empty::<i32>( ).unique_ext( );
+    assert_eq!((0, Some(0)), iterator.size_hint( ));
+    assert_eq!(None, iterator.next( ));
+  }
+
+  #[test]
+  fn case_UniqueBy( ) {
+    #[derive(Debug, PartialEq)]
+    struct User {
+      id: u32,
+      name: &'static str
+    }
+
+    let users= vec![
+      User { id: 1, name: "a" },
+      User { id: 2, name: "b" },
+      User { id: 1, name: "c" },
+      User { id: 3, name: "d" },
+      User { id: 2, name: "e" }
+    ];
+
+    let names: Vec<_>= users.into_iter( )
+                            .unique_by_ext(|user| user.id)
+                            .map(|user| user.name)
+                            .collect( );
+    assert_eq!(vec![ "a", "b", "d" ], names);
+  }
+
+  #[test]
+  fn case_Stride( ) {
+    for step in 1..=11 {
+      for offset in 0..=11 {
+        let expected: Vec<_>= (0..10).filter(|i| *i >= offset && (i - offset) % step == 0)
+                                     .collect( );
+
+        let iterator= (0..10).stride_ext(step, offset);
+        assert_eq!(expected.len( ), iterator.len( ));
+        assert_eq!(expected, iterator.collect::<Vec<_>>( ));
+
+        let reversed: Vec<_>= (0..10).stride_ext(step, offset).rev( ).collect( );
+        assert_eq!(expected.iter( ).rev( ).copied( ).collect::<Vec<_>>( ), reversed);
+
+        // Consuming from both the ends shouldn't yield any item twice, or skip any.
+        for pattern in [ 0b0101, 0b1010, 0b0011, 0b1100 ] {
+          assert_eq!(expected, consumeFromBothEnds((0..10).stride_ext(step, offset), pattern));
+        }
+      }
+    }
+  }
+
+  #[test]
+  #[should_panic(expected = "step must be non-zero")]
+  fn case_StrideWithZeroStep( ) {
+    (0..10).stride_ext(0, 0);
+  }
+
+  // Only implements next, so that the default (item by item) nth and last get used.
+  struct ByNext<I>(I);
+
+  impl<I> Iterator for ByNext<I>
+    where I: Iterator
+  {
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+      self.0.next( )
+    }
+  }
+
+  #[test]
+  fn case_FlattenNthAndLastMatchNaive( ) {
+    let mut state= 0x9E3779B97F4A7C15;
+
+    for _ in 0..1000 {
+      let nested: Vec<Vec<u64>>= (0..xorshift(&mut state) % 6)
+        .map(|_| (0..xorshift(&mut state) % 5).map(|_| xorshift(&mut state) % 100).collect( ))
+        .collect( );
+      let mut flattened: Vec<_>= nested.iter( ).flatten( ).copied( ).collect( );
+
+      // Some items are consumed from the back first, so that the back inner iterator is (likely)
+      // in progress.
+      let mut fast= flatten(nested);
+      for _ in 0..xorshift(&mut state) % 3 {
+        assert_eq!(flattened.pop( ), fast.next_back( ));
+      }
+
+      let total= flattened.len( ) as u64;
+      let mut naive= ByNext(flattened.into_iter( ));
+
+      for _ in 0..xorshift(&mut state) % 4 {
+        let n= (xorshift(&mut state) % (total + 2)) as usize;
+        assert_eq!(naive.nth(n), fast.nth(n));
+      }
+      assert_eq!(naive.last( ), fast.last( ));
+    }
+  }
+
+  #[test]
+  fn case_FlattenNthSkipsExactInnerIterators( ) {
+    let polled= std::rc::Rc::new(std::cell::Cell::new(0));
+
+    let nested= (0..1000).map(|i| {
+      let polled= polled.clone( );
+      (0..1000).map(move |j| i * 1000 + j).inspect(move |_| polled.set(polled.get( ) + 1))
+    });
+    let mut iterator= flatten(nested);
+
+    assert_eq!(Some(999_500), iterator.nth(999_500));
+    assert!(polled.get( ) <= 1000, "polled {} items", polled.get( ));
+
+    assert_eq!(Some(999_999), iterator.last( ));
+    assert!(polled.get( ) <= 2000, "polled {} items", polled.get( ));
+  }
+
+  #[test]
+  fn case_Batching( ) {
+    let pairUp= |iterator: &mut std::ops::Range<i32>| Some((iterator.next( )?, iterator.next( )?));
+
+    let mut iterator= (0..5).batching_ext(pairUp);
+    assert_eq!((0, None), iterator.size_hint( ));
+    assert_eq!(Some((0, 1)), iterator.next( ));
+    assert_eq!(Some((2, 3)), iterator.next( ));
+    // 4 doesn't have a partner.
+    assert_eq!(None, iterator.next( ));
+
+    // Terminates early, even though the iterator isn't exhausted.
+    let iterator= (0..10).batching_ext(|iterator| iterator.next( ).filter(|item| *item < 3));
+    assert_eq!(vec![ 0, 1, 2 ], iterator.collect::<Vec<_>>( ));
+
+    // Each batch is a Vec of upto 3 items, which then gets flattened back.
+    let batches= (0..8).batching_ext(|iterator| {
+      let batch: Vec<_>= iterator.take(3).collect( );
+      (!batch.is_empty( )).then_some(batch)
+    });
+    assert_eq!((0..8).collect::<Vec<_>>( ), batches.flatten_ext( ).collect::<Vec<_>>( ));
+  }
+
+  #[test]
+  fn case_FlattenDeep2( ) {
+    let nested= vec![
+      vec![ vec![ 1, 2 ], vec![ ], vec![ 3 ] ],
+      vec![ ],
+      vec![ vec![ ], vec![ 4, 5, 6 ] ],
+      vec![ vec![ 7 ] ]
+    ];
+    let expected: Vec<_>= (1..=7).collect( );
+
+    assert_eq!(expected, nested.clone( ).into_iter( ).flatten_deep2( ).collect::<Vec<_>>( ));
+    assert_eq!(expected.iter( ).rev( ).copied( ).collect::<Vec<_>>( ),
+               nested.clone( ).into_iter( ).flatten_deep2( ).rev( ).collect::<Vec<_>>( ));
+
+    for pattern in 0..(1 << 7) {
+      assert_eq!(expected,
+                 consumeFromBothEnds(nested.clone( ).into_iter( ).flatten_deep2( ), pattern));
+    }
+
+    let empty: Vec<Vec<Vec<i32>>>= vec![ vec![ ], vec![ vec![ ] ] ];
+    assert_eq!(None, empty.into_iter( ).flatten_deep2( ).next_back( ));
+  }
+
+  #[test]
+  fn case_FlattenDeep2SizeHint( ) {
+    let nested= vec![ vec![ vec![ 1, 2 ], vec![ 3 ] ], vec![ vec![ 4, 5 ] ] ];
+    let mut iterator= nested.into_iter( ).flatten_deep2( );
+
+    // Nothing's known about the middle and the inner iterators which haven't been pulled out yet.
+    assert_eq!((0, None), iterator.size_hint( ));
+
+    assert_eq!(Some(1), iterator.next( ));
+    assert_eq!((1, None), iterator.size_hint( ));
+
+    assert_eq!(Some(5), iterator.next_back( ));
+    assert_eq!((2, None), iterator.size_hint( ));
+
+    // Now, the outer iterator has nothing left. But the front middle iterator still has [ 3 ].
+    assert_eq!(Some(2), iterator.next( ));
+    assert_eq!((1, None), iterator.size_hint( ));
+
+    // Only the inner iterators are left now.
+    assert_eq!(Some(3), iterator.next( ));
+    assert_eq!((1, Some(1)), iterator.size_hint( ));
+
+    assert_eq!(Some(4), iterator.next( ));
+    assert_eq!((0, Some(0)), iterator.size_hint( ));
+    assert_eq!(None, iterator.next( ));
+  }
+
+  #[test]
+  fn case_CycleN( ) {
+    let iterator= (1..4).cycle_n_ext(2);
+    assert_eq!(6, iterator.len( ));
+    assert_eq!(vec![ 1, 2, 3, 1, 2, 3 ], iterator.collect::<Vec<_>>( ));
+
+    assert_eq!((1..=3).collect::<Vec<_>>( ), (1..=3).cycle_n_ext(1).collect::<Vec<_>>( ));
+
+    let mut iterator= (1..=3).cycle_n_ext(0);
+    assert_eq!((0, Some(0)), iterator.size_hint( ));
+    assert_eq!(None, iterator.next( ));
+
+    // Mustn't go through each of the rounds one by one.
+    let mut iterator= (0..0).cycle_n_ext(usize::MAX);
+    assert_eq!(None, iterator.next( ));
+    assert_eq!((0, Some(0)), iterator.size_hint( ));
+
+    let mut iterator= (0..2).cycle_n_ext(usize::MAX);
+    assert_eq!((usize::MAX, None), iterator.size_hint( ));
+    iterator.next( );
+    assert_eq!((usize::MAX, None), iterator.size_hint( ));
+
+    let mut iterator= vec![ 1, 2 ].into_iter( ).cycle_n_ext(3);
+    iterator.next( );
+    assert_eq!((5, Some(5)), iterator.size_hint( ));
+  }
+
+  #[test]
+  fn case_Cycle( ) {
+    assert_eq!(vec![ 1, 2, 1, 2, 1 ], (1..=2).cycle_ext( ).take(5).collect::<Vec<_>>( ));
+    assert_eq!((usize::MAX, None), (1..=2).cycle_ext( ).size_hint( ));
+
+    let mut iterator= (0..0).cycle_ext( );
+    assert_eq!((0, Some(0)), iterator.size_hint( ));
+    assert_eq!(None, iterator.next( ));
+  }
+
+  #[test]
+  fn case_InspectIndexed( ) {
+    let mut observed= Vec::new( );
+    let items: Vec<_>= "abcde".chars( )
+                              .inspect_indexed_ext(|index, item| observed.push((index, *item)))
+                              .skip(2)
+                              .collect( );
+    assert_eq!(vec![ 'c', 'd', 'e' ], items);
+    assert_eq!(vec![ (0, 'a'), (1, 'b'), (2, 'c'), (3, 'd'), (4, 'e') ], observed);
+
+    let mut observed= Vec::new( );
+    let mut iterator= vec![ 'a', 'b', 'c', 'd' ].into_iter( )
+                        .inspect_indexed_ext(|index, item| observed.push((index, *item)));
+    assert_eq!(4, iterator.len( ));
+    iterator.next( );
+    iterator.next_back( );
+    iterator.next_back( );
+    iterator.next( );
+    assert_eq!(None, iterator.next( ));
+    assert_eq!(vec![ (0, 'a'), (3, 'd'), (2, 'c'), (1, 'b') ], observed);
+  }
+
+  #[test]
+  fn case_EnumerateFrom( ) {
+    let iterator= vec![ 'a', 'b', 'c' ].into_iter( ).enumerate_from_ext(1000u64);
+    assert_eq!(3, iterator.len( ));
+    assert_eq!(vec![ (1000, 'a'), (1001, 'b'), (1002, 'c') ], iterator.collect::<Vec<_>>( ));
+
+    let reversed: Vec<_>= vec![ 'a', 'b', 'c' ].into_iter( ).enumerate_from_ext(1000u64).rev( )
+                                                .collect( );
+    assert_eq!(vec![ (1002, 'c'), (1001, 'b'), (1000, 'a') ], reversed);
+
+    let mut iterator= (0..10).enumerate_from_ext(5usize).skip(3);
+    assert_eq!(Some((8, 3)), iterator.next( ));
+    assert_eq!(Some((14, 9)), iterator.next_back( ));
+  }
+
+  #[test]
+  fn case_CollectVecExact( ) {
+    let mut iterator= flatten(vec![ vec![ 1, 2, 3 ], vec![ 4, 5, 6, 7 ] ]);
+
+    // Once both the inner iterators are in progress, the size_hint is exact.
+    assert_eq!(Some(1), iterator.next( ));
+    assert_eq!(Some(7), iterator.next_back( ));
+    assert_eq!((5, Some(5)), iterator.size_hint( ));
+
+    let items= iterator.collect_vec_exact( );
+    assert_eq!(vec![ 2, 3, 4, 5, 6 ], items);
+    assert_eq!(5, items.capacity( ));
+
+    let items= (0..10).stride_ext(3, 1).collect_vec_exact( );
+    assert_eq!(vec![ 1, 4, 7 ], items);
+    assert_eq!(3, items.capacity( ));
+  }
+
+  #[test]
+  fn case_TryCollectVec( ) {
+    let pulled= std::cell::Cell::new(0);
+    let results= (0..10).map(|i| {
+      pulled.set(pulled.get( ) + 1);
+      if i == 3 { Err(i) } else { Ok(i) }
+    });
+
+    assert_eq!(Err(3), results.try_collect_vec( ));
+    assert_eq!(4, pulled.get( ));
+
+    let results= (0..3).map(Ok::<_, ( )>);
+    assert_eq!(Ok(vec![ 0, 1, 2 ]), results.try_collect_vec( ));
+  }
+}
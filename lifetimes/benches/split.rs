@@ -0,0 +1,34 @@
+// Compares splitting on a single byte using memchr (Byte), against the generic &str search.
+// Run with : cargo bench --features memchr
+
+use std::{hint::black_box, time::{Duration, Instant}};
+
+use lifetimes::{Byte, Delimeter, StrSplit};
+
+const ITERATIONS: u32 = 50;
+
+fn bench<D>(name: &str, haystack: &str, delimeter: D)
+  where
+    D: Delimeter + Copy
+{
+  let mut elapsed = Duration::ZERO;
+  let mut segments = 0;
+
+  for _ in 0..ITERATIONS {
+    let start = Instant::now( );
+    segments = black_box(StrSplit::new(black_box(haystack), delimeter).count( ));
+    elapsed += start.elapsed( );
+  }
+
+  println!("{name:>12} : {:?} per iteration ({segments} segments)", elapsed / ITERATIONS);
+}
+
+fn main( ) {
+  // Something like a large log file : long lines, with the delimeter occurring rarely.
+  let line = "2024-01-01T00:00:00Z INFO request handled in 12ms by worker α-7 for client 10.0.0.1";
+  let haystack = [line; 100_000].join("\n");
+
+  bench("&str", &haystack, "\n");
+  bench("char", &haystack, '\n');
+  bench("Byte", &haystack, Byte::new(b'\n'));
+}
@@ -1,28 +1,418 @@
 #![allow(non_snake_case, unused)]
 
-#[derive(Debug)]
+use std::{iter::FusedIterator, ops::Range, rc::Rc, sync::Arc};
+
+#[derive(Debug, Clone)]
 pub struct StrSplit<'haystack, D> {
-  remainder: Option<&'haystack str>,
+  haystack: &'haystack str,
   delimeter: D,
+
+  // Start of the remainder, within the haystack. None once the whole haystack has been yielded.
+  position: Option<usize>,
+
+  // Whether there has been a match already. If so, an empty match can't be right at the start of
+  // the remainder (see find_next_match).
+  after_match: bool,
+
+  // Number of segments which can still be yielded (if there's a limit). The last one of them is
+  // the whole remainder, delimeters and all.
+  remaining_segments: Option<usize>,
+
+  skip_empty: bool,
+  trim: bool,
+
+  // Whether the last segment should be skipped, if it's empty (like std's split_terminator).
+  skip_trailing_empty: bool,
 }
 
 impl<'haystack, D> StrSplit<'haystack, D> {
   pub fn new(haystack: &'haystack str, delimeter: D) -> Self {
     Self {
-      remainder: Some(haystack),
+      haystack,
       delimeter,
+      position: Some(0),
+      after_match: false,
+      remaining_segments: None,
+      skip_empty: false,
+      trim: false,
+      skip_trailing_empty: false,
     }
   }
+
+  // Treats the delimeter as a terminator, like std's split_terminator. So, there's no trailing
+  // empty segment if the haystack ends with the delimeter.
+  pub fn terminator(haystack: &'haystack str, delimeter: D) -> Self {
+    Self {
+      skip_trailing_empty: true,
+      ..Self::new(haystack, delimeter)
+    }
+  }
+
+  // Yields at most n segments, like std's splitn. Segments skipped due to skip_empty don't count.
+  pub fn limit(mut self, n: usize) -> Self {
+    self.remaining_segments = Some(n);
+    self
+  }
+
+  // Whether empty segments (after trimming, if trim is set) should be skipped.
+  pub fn skip_empty(mut self, skip_empty: bool) -> Self {
+    self.skip_empty = skip_empty;
+    self
+  }
+
+  // Whether whitespace should be trimmed from both ends of each segment. Trimming just narrows down
+  // the segment, so it still borrows from the haystack.
+  pub fn trim(mut self, trim: bool) -> Self {
+    self.trim = trim;
+    self
+  }
+
+  // What hasn't been yielded yet. It borrows from the haystack (and not from the StrSplit), so it
+  // can outlive the StrSplit.
+  pub fn remainder(&self) -> Option<&'haystack str> {
+    self.position.map(|position| &self.haystack[position..])
+  }
+
+  pub fn into_remainder(self) -> Option<&'haystack str> {
+    self.remainder( )
+  }
+
+  pub fn is_finished(&self) -> bool {
+    self.position.is_none( )
+  }
+
+  fn take_remainder(&mut self) -> Option<&'haystack str> {
+    let remainder = self.remainder( );
+    self.position = None;
+    remainder
+  }
+}
+
+impl<'haystack> StrSplit<'haystack, fn(char) -> bool> {
+  // Splits on runs of (Unicode) whitespace, and never yields empty segments. Same as std's
+  // split_whitespace.
+  pub fn whitespace(haystack: &'haystack str) -> Self {
+    Self::new(haystack, char::is_whitespace as fn(char) -> bool).skip_empty(true)
+  }
+}
+
+// Splits the haystack around the first occurrence of the delimeter.
+pub fn split_once<D>(haystack: &str, delimeter: D) -> Option<(&str, &str)>
+  where
+    D: Delimeter
+{
+  let (delim_start, delim_end) = delimeter.find_next(haystack)?;
+  Some((&haystack[..delim_start], &haystack[delim_end..]))
 }
 
 pub trait Delimeter {
   fn find_next(&self, s: &str) -> Option<(usize, usize)>;
+
+  // Finds the first match which starts at or after start. Unlike with find_next, the returned
+  // offsets are relative to the whole haystack. By default, this just searches haystack[start..].
+  // Implementors whose matches depend on what comes before start (like a regex with \b or ^)
+  // should override it.
+  fn find_next_at(&self, haystack: &str, start: usize) -> Option<(usize, usize)> {
+    let (delim_start, delim_end) = self.find_next(&haystack[start..])?;
+    Some((start + delim_start, start + delim_end))
+  }
+
+  // Finds the match which starts last. By default, this keeps calling find_next (each time starting
+  // right after where the previous match started) until there are no more matches. Implementors
+  // which can search backwards should override it.
+  fn find_last(&self, s: &str) -> Option<(usize, usize)> {
+    let mut last_match = None;
+    let mut search_from = 0;
+
+    while let Some((delim_start, delim_end)) = self.find_next(&s[search_from..]) {
+      let (delim_start, delim_end) = (search_from + delim_start, search_from + delim_end);
+      last_match = Some((delim_start, delim_end));
+
+      match s[delim_start..].chars( ).next( ) {
+        Some(c) => search_from = delim_start + c.len_utf8( ),
+        None => break,
+      }
+    }
+    last_match
+  }
+
+  // Matches whichever of the 2 delimeters occurs first. See Or.
+  fn or<B>(self, other: B) -> Or<Self, B>
+    where
+      Self: Sized
+  {
+    Or(self, other)
+  }
+
+  // Splits right before each match, so the matched part stays at the start of the next segment.
+  // See Before.
+  fn before(self) -> Before<Self>
+    where
+      Self: Sized
+  {
+    Before(self)
+  }
 }
 
 impl Delimeter for &str {
   fn find_next(&self, s: &str) -> Option<(usize, usize)> {
     s.find(self).map(|start| (start, start + self.len( )))
   }
+
+  fn find_last(&self, s: &str) -> Option<(usize, usize)> {
+    s.rfind(self).map(|start| (start, start + self.len( )))
+  }
+}
+
+impl Delimeter for char {
+  fn find_next(&self, s: &str) -> Option<(usize, usize)> {
+    s.find(*self).map(|start| (start, start + self.len_utf8( )))
+  }
+
+  fn find_last(&self, s: &str) -> Option<(usize, usize)> {
+    s.rfind(*self).map(|start| (start, start + self.len_utf8( )))
+  }
+}
+
+// Matches any single character for which the closure returns true.
+impl<F> Delimeter for F
+  where
+    F: Fn(char) -> bool
+{
+  fn find_next(&self, s: &str) -> Option<(usize, usize)> {
+    s.char_indices( )
+      .find(|(_, c)| self(*c))
+      .map(|(start, c)| (start, start + c.len_utf8( )))
+  }
+
+  fn find_last(&self, s: &str) -> Option<(usize, usize)> {
+    s.char_indices( )
+      .rev( )
+      .find(|(_, c)| self(*c))
+      .map(|(start, c)| (start, start + c.len_utf8( )))
+  }
+}
+
+// Matches delim, but only outside quotes (so, "a,b",c is 2 fields when splitting on ','). Quotes
+// aren't removed from the segments. A quote (or delim) right after the escape character is taken
+// literally. An unterminated quote makes the rest of the haystack a single segment.
+// NOTE - The escape character shouldn't be the quote itself. CSV style doubled quotes ("") already
+// work without an escape character, since the quoting gets toggled twice.
+#[derive(Debug, Clone, Copy)]
+pub struct QuotedDelimeter {
+  pub delim: char,
+  pub quote: char,
+  pub escape: Option<char>,
+}
+
+impl Delimeter for QuotedDelimeter {
+  // Each search starts outside quotes, since the previous match (if any) was outside quotes.
+  fn find_next(&self, s: &str) -> Option<(usize, usize)> {
+    let mut in_quotes = false;
+    let mut escaped = false;
+
+    for (start, c) in s.char_indices( ) {
+      if escaped {
+        escaped = false;
+      } else if Some(c) == self.escape {
+        escaped = true;
+      } else if c == self.quote {
+        in_quotes = !in_quotes;
+      } else if c == self.delim && !in_quotes {
+        return Some((start, start + c.len_utf8( )));
+      }
+    }
+    None
+  }
+}
+
+// Matches whichever of the 2 delimeters occurs first. If both the matches start at the same
+// position, then the longer one wins.
+#[derive(Debug, Clone, Copy)]
+pub struct Or<A, B>(pub A, pub B);
+
+impl<A, B> Delimeter for Or<A, B>
+  where
+    A: Delimeter,
+    B: Delimeter
+{
+  fn find_next(&self, s: &str) -> Option<(usize, usize)> {
+    self.find_next_at(s, 0)
+  }
+
+  fn find_next_at(&self, haystack: &str, start: usize) -> Option<(usize, usize)> {
+    match (self.0.find_next_at(haystack, start), self.1.find_next_at(haystack, start)) {
+      (Some(a), Some(b)) if b.0 < a.0 || (b.0 == a.0 && b.1 > a.1) => Some(b),
+      (Some(a), _) => Some(a),
+      (a, b) => a.or(b),
+    }
+  }
+
+  // Likewise, the match which starts last wins, and the longer one on a tie.
+  fn find_last(&self, s: &str) -> Option<(usize, usize)> {
+    match (self.0.find_last(s), self.1.find_last(s)) {
+      (Some(a), Some(b)) => Some(a.max(b)),
+      (a, b) => a.or(b),
+    }
+  }
+}
+
+// Turns each match of the inner delimeter into an empty match right where it starts. So, nothing
+// gets dropped from the haystack, and each segment (except maybe the first one) starts with what
+// the inner delimeter matched. For example, splitting "a1b2" before each digit gives "a", "1b"
+// and "2".
+// NOTE - Doesn't work with RStrSplit. After an empty match, RStrSplit leaves out the last character
+// of the remainder when searching again. But that's exactly the character which the inner
+// delimeter needs to see, for the next match.
+#[derive(Debug, Clone, Copy)]
+pub struct Before<D>(pub D);
+
+impl<D> Delimeter for Before<D>
+  where
+    D: Delimeter
+{
+  fn find_next(&self, s: &str) -> Option<(usize, usize)> {
+    self.0.find_next(s).map(|(start, _)| (start, start))
+  }
+
+  fn find_next_at(&self, haystack: &str, start: usize) -> Option<(usize, usize)> {
+    self.0.find_next_at(haystack, start).map(|(delim_start, _)| (delim_start, delim_start))
+  }
+
+  fn find_last(&self, s: &str) -> Option<(usize, usize)> {
+    self.0.find_last(s).map(|(start, _)| (start, start))
+  }
+}
+
+// A single ASCII byte delimeter, searched for using memchr (which is much faster than the generic
+// &str search, for a 1 byte pattern).
+// The byte needs to be ASCII, since a non-ASCII byte could match in the middle of a (multibyte)
+// character.
+#[cfg(feature = "memchr")]
+#[derive(Debug, Clone, Copy)]
+pub struct Byte(u8);
+
+#[cfg(feature = "memchr")]
+impl Byte {
+  pub fn new(byte: u8) -> Self {
+    assert!(byte.is_ascii( ), "the byte delimeter must be ASCII");
+    Self(byte)
+  }
+}
+
+#[cfg(feature = "memchr")]
+impl Delimeter for Byte {
+  fn find_next(&self, s: &str) -> Option<(usize, usize)> {
+    memchr::memchr(self.0, s.as_bytes( )).map(|start| (start, start + 1))
+  }
+
+  fn find_last(&self, s: &str) -> Option<(usize, usize)> {
+    memchr::memrchr(self.0, s.as_bytes( )).map(|start| (start, start + 1))
+  }
+}
+
+// Owned strings (like delimeters which are only known at runtime) work just like &str. A blanket
+// impl over AsRef<str> isn't possible, since it would overlap with the impls for char and the
+// like.
+macro_rules! impl_delimeter_for_owned_str {
+  ($($type:ty),*) => {
+    $(
+      impl Delimeter for $type {
+        fn find_next(&self, s: &str) -> Option<(usize, usize)> {
+          (&**self).find_next(s)
+        }
+
+        fn find_last(&self, s: &str) -> Option<(usize, usize)> {
+          (&**self).find_last(s)
+        }
+      }
+    )*
+  };
+}
+
+impl_delimeter_for_owned_str!(String, Box<str>, Rc<str>, Arc<str>);
+
+// The forward splitters search the whole haystack (using find_at), so ^, \b and the like see what
+// comes before the remainder. Splitting gives the same segments as Regex::split.
+// NOTE - find_last (used by RStrSplit and rsplit_once) only sees the part of the haystack before
+// the remainder's end. So there, $ (and \b etc.) match at the end of each remainder.
+#[cfg(feature = "regex")]
+impl Delimeter for regex::Regex {
+  fn find_next(&self, s: &str) -> Option<(usize, usize)> {
+    self.find(s).map(|found| (found.start( ), found.end( )))
+  }
+
+  fn find_next_at(&self, haystack: &str, start: usize) -> Option<(usize, usize)> {
+    self.find_at(haystack, start).map(|found| (found.start( ), found.end( )))
+  }
+
+  // The last of the (leftmost-first) matches, which isn't always the one which starts last (when
+  // matches can overlap).
+  fn find_last(&self, s: &str) -> Option<(usize, usize)> {
+    self.find_iter(s).last( ).map(|found| (found.start( ), found.end( )))
+  }
+}
+
+#[cfg(feature = "regex")]
+impl Delimeter for &regex::Regex {
+  fn find_next(&self, s: &str) -> Option<(usize, usize)> {
+    (**self).find_next(s)
+  }
+
+  fn find_next_at(&self, haystack: &str, start: usize) -> Option<(usize, usize)> {
+    (**self).find_next_at(haystack, start)
+  }
+
+  fn find_last(&self, s: &str) -> Option<(usize, usize)> {
+    (**self).find_last(s)
+  }
+}
+
+// Matches any one of the listed characters (whichever occurs first). The haystack is scanned just
+// once. An empty list never matches.
+impl Delimeter for &[char] {
+  fn find_next(&self, s: &str) -> Option<(usize, usize)> {
+    s.char_indices( )
+      .find(|(_, c)| self.contains(c))
+      .map(|(start, c)| (start, start + c.len_utf8( )))
+  }
+
+  fn find_last(&self, s: &str) -> Option<(usize, usize)> {
+    s.char_indices( )
+      .rev( )
+      .find(|(_, c)| self.contains(c))
+      .map(|(start, c)| (start, start + c.len_utf8( )))
+  }
+}
+
+impl<const N: usize> Delimeter for [char; N] {
+  fn find_next(&self, s: &str) -> Option<(usize, usize)> {
+    self.as_slice( ).find_next(s)
+  }
+
+  fn find_last(&self, s: &str) -> Option<(usize, usize)> {
+    self.as_slice( ).find_last(s)
+  }
+}
+
+impl<'haystack, D> StrSplit<'haystack, D>
+  where
+    D: Delimeter
+{
+  // The segment until the next delimeter, without considering the limit, skip_empty or trim.
+  fn next_segment(&mut self) -> Option<&'haystack str> {
+    let start = self.position?;
+
+    if let Some((delim_start, delim_end)) =
+      find_next_match(&self.delimeter, self.haystack, start, self.after_match)
+    {
+      self.position = Some(delim_end);
+      self.after_match = true;
+      Some(&self.haystack[start..delim_start])
+    } else {
+      self.take_remainder( )
+    }
+  }
 }
 
 impl<'haystack, D> Iterator for StrSplit<'haystack, D>
@@ -32,14 +422,1148 @@ impl<'haystack, D> Iterator for StrSplit<'haystack, D>
   type Item = &'haystack str;
 
   fn next(&mut self) -> Option<Self::Item> {
-    let remainder = &mut self.remainder?;
+    loop {
+      let segment = match self.remaining_segments {
+        Some(0) => return None,
+        Some(1) => self.take_remainder( )?,
+        _ => self.next_segment( )?,
+      };
 
-    if let Some((delim_start, delim_end)) = self.delimeter.find_next(remainder) {
-      let until_delimeter = &remainder[..delim_start];
-      self.remainder = Some(&remainder[delim_end..]);
-      Some(until_delimeter)
+      let segment = if self.trim { segment.trim( ) } else { segment };
+
+      if self.skip_empty && segment.is_empty( ) {
+        continue;
+      }
+
+      if self.skip_trailing_empty && segment.is_empty( ) && self.position.is_none( ) {
+        return None;
+      }
+
+      if let Some(ref mut n) = self.remaining_segments {
+        *n -= 1;
+      }
+      return Some(segment);
+    }
+  }
+}
+
+// Once exhausted, the position is None (so, nothing's left to be searched).
+impl<'haystack, D> FusedIterator for StrSplit<'haystack, D>
+  where
+    D: Delimeter
+{ }
+
+// Matches the pattern, ignoring ASCII case. The span is that of the matched bytes in the haystack
+// (whose case may differ from that of the pattern).
+#[derive(Debug, Clone, Copy)]
+pub struct AsciiCaseInsensitive<P>(pub P);
+
+impl Delimeter for AsciiCaseInsensitive<&str> {
+  fn find_next(&self, s: &str) -> Option<(usize, usize)> {
+    let pattern = self.0.as_bytes( );
+    if pattern.is_empty( ) {
+      return Some((0, 0));
+    }
+
+    // ASCII case folding only ever maps an ASCII byte to another ASCII byte, and the non-ASCII
+    // bytes need to match exactly. So, a match always starts and ends at a character boundary.
+    s.as_bytes( )
+      .windows(pattern.len( ))
+      .position(|window| window.eq_ignore_ascii_case(pattern))
+      .map(|start| (start, start + pattern.len( )))
+  }
+}
+
+// Matches the pattern, ignoring case. Only simple case folding is done : characters are compared
+// one to one (by their lowercase forms), so something like 'ß' won't match "ss".
+#[derive(Debug, Clone, Copy)]
+pub struct CaseInsensitive<P>(pub P);
+
+impl Delimeter for CaseInsensitive<&str> {
+  fn find_next(&self, s: &str) -> Option<(usize, usize)> {
+    let matches_at = |start: usize| {
+      let mut haystack_chars = s[start..].char_indices( );
+
+      for pattern_char in self.0.chars( ) {
+        let (_, haystack_char) = haystack_chars.next( )?;
+
+        if !haystack_char.to_lowercase( ).eq(pattern_char.to_lowercase( )) {
+          return None;
+        }
+      }
+
+      let end = haystack_chars.next( ).map_or(s.len( ), |(offset, _)| start + offset);
+      Some((start, end))
+    };
+
+    s.char_indices( )
+      .map(|(start, _)| start)
+      .chain(std::iter::once(s.len( )))
+      .find_map(matches_at)
+  }
+}
+
+// Yields each occurrence of the delimeter, along with its byte offset in the haystack (like std's
+// match_indices). Occurrences don't overlap : after a match, searching resumes from its end.
+#[derive(Debug, Clone)]
+pub struct MatchIndices<'haystack, D> {
+  haystack: &'haystack str,
+  delimeter: D,
+
+  // Where the search resumes from, within the haystack.
+  position: usize,
+
+  after_match: bool,
+}
+
+impl<'haystack, D> MatchIndices<'haystack, D> {
+  pub fn new(haystack: &'haystack str, delimeter: D) -> Self {
+    Self {
+      haystack,
+      delimeter,
+      position: 0,
+      after_match: false,
+    }
+  }
+}
+
+impl<'haystack, D> Iterator for MatchIndices<'haystack, D>
+  where
+    D: Delimeter
+{
+  type Item = (usize, &'haystack str);
+
+  fn next(&mut self) -> Option<Self::Item> {
+    let (delim_start, delim_end) =
+      find_next_match(&self.delimeter, self.haystack, self.position, self.after_match)?;
+
+    self.position = delim_end;
+    self.after_match = true;
+    Some((delim_start, &self.haystack[delim_start..delim_end]))
+  }
+}
+
+impl<'haystack, D> FusedIterator for MatchIndices<'haystack, D>
+  where
+    D: Delimeter
+{ }
+
+// Yields each segment, along with its span in the haystack.
+#[derive(Debug, Clone)]
+pub struct SplitWithSpans<'haystack, D> {
+  haystack: &'haystack str,
+  segments: StrSplit<'haystack, D>,
+}
+
+impl<'haystack, D> SplitWithSpans<'haystack, D> {
+  pub fn new(haystack: &'haystack str, delimeter: D) -> Self {
+    Self {
+      haystack,
+      segments: StrSplit::new(haystack, delimeter),
+    }
+  }
+}
+
+impl<'haystack, D> Iterator for SplitWithSpans<'haystack, D>
+  where
+    D: Delimeter
+{
+  type Item = (Range<usize>, &'haystack str);
+
+  fn next(&mut self) -> Option<Self::Item> {
+    let segment = self.segments.next( )?;
+
+    // Each segment is a subslice of the haystack. So, its offset is just the distance between
+    // where they start.
+    let start = segment.as_ptr( ) as usize - self.haystack.as_ptr( ) as usize;
+    Some((start..(start + segment.len( )), segment))
+  }
+}
+
+impl<'haystack, D> FusedIterator for SplitWithSpans<'haystack, D>
+  where
+    D: Delimeter
+{ }
+
+// Like StrSplit, but owns the haystack. Since an Iterator can't yield items borrowing from itself,
+// it yields the spans of the segments (within the haystack) instead. Use next_segment to get the
+// segments themselves.
+#[derive(Debug, Clone)]
+pub struct OwnedStrSplit<D> {
+  haystack: String,
+  delimeter: D,
+
+  // Start of the remainder, within the haystack.
+  position: Option<usize>,
+
+  after_match: bool,
+}
+
+impl<D> OwnedStrSplit<D> {
+  pub fn new(haystack: String, delimeter: D) -> Self {
+    Self {
+      haystack,
+      delimeter,
+      position: Some(0),
+      after_match: false,
+    }
+  }
+
+  pub fn haystack(&self) -> &str {
+    &self.haystack
+  }
+
+  pub fn into_haystack(self) -> String {
+    self.haystack
+  }
+}
+
+impl<D> OwnedStrSplit<D>
+  where
+    D: Delimeter
+{
+  // The next segment, borrowed from the OwnedStrSplit.
+  pub fn next_segment(&mut self) -> Option<&str> {
+    let span = self.next( )?;
+    Some(&self.haystack[span])
+  }
+}
+
+impl<D> Iterator for OwnedStrSplit<D>
+  where
+    D: Delimeter
+{
+  type Item = Range<usize>;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    let start = self.position?;
+
+    if let Some((delim_start, delim_end)) =
+      find_next_match(&self.delimeter, &self.haystack, start, self.after_match)
+    {
+      self.position = Some(delim_end);
+      self.after_match = true;
+      Some(start..delim_start)
     } else {
-      self.remainder.take( )
+      self.position = None;
+      Some(start..self.haystack.len( ))
     }
   }
-}
\ No newline at end of file
+}
+
+impl<D> FusedIterator for OwnedStrSplit<D>
+  where
+    D: Delimeter
+{ }
+
+// Like StrSplit, but goes from the end of the haystack towards its start (like std's rsplit). So,
+// the last segment is yielded first.
+#[derive(Debug, Clone)]
+pub struct RStrSplit<'haystack, D> {
+  remainder: Option<&'haystack str>,
+  delimeter: D,
+
+  // Same as for StrSplit, except that the empty match was at the very end of the remainder.
+  after_empty_match: bool,
+}
+
+impl<'haystack, D> RStrSplit<'haystack, D> {
+  pub fn new(haystack: &'haystack str, delimeter: D) -> Self {
+    Self {
+      remainder: Some(haystack),
+      delimeter,
+      after_empty_match: false,
+    }
+  }
+}
+
+impl<'haystack, D> Iterator for RStrSplit<'haystack, D>
+  where
+    D: Delimeter
+{
+  type Item = &'haystack str;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    let remainder = self.remainder?;
+
+    // After an empty match, the last character is skipped before searching again.
+    let search_until = match remainder.chars( ).next_back( ) {
+      Some(c) if self.after_empty_match => remainder.len( ) - c.len_utf8( ),
+      None if self.after_empty_match => return self.remainder.take( ),
+      _ => remainder.len( ),
+    };
+
+    if let Some((delim_start, delim_end)) = self.delimeter.find_last(&remainder[..search_until]) {
+      let after_delimeter = &remainder[delim_end..];
+      self.remainder = Some(&remainder[..delim_start]);
+      self.after_empty_match = delim_start == delim_end;
+      Some(after_delimeter)
+    } else {
+      self.remainder.take( )
+    }
+  }
+}
+
+impl<'haystack, D> FusedIterator for RStrSplit<'haystack, D>
+  where
+    D: Delimeter
+{ }
+
+// Splits the haystack around the last occurrence of the delimeter.
+pub fn rsplit_once<D>(haystack: &str, delimeter: D) -> Option<(&str, &str)>
+  where
+    D: Delimeter
+{
+  let (delim_start, delim_end) = delimeter.find_last(haystack)?;
+  Some((&haystack[..delim_start], &haystack[delim_end..]))
+}
+
+// Parses something like a query string ("a=1&b=&c") into (key, value) pairs. A pair without the
+// key-value delimeter has no value, and empty pairs are skipped. Both the keys and the values
+// borrow from s (and not from the iterator, or the delimeters).
+pub fn parse_pairs<P, K>(
+  s: &str,
+  pair_delimeter: P,
+  key_value_delimeter: K,
+) -> impl Iterator<Item = (&str, Option<&str>)>
+  where
+    P: Delimeter,
+    K: Delimeter + Clone
+{
+  StrSplit::new(s, pair_delimeter)
+    .skip_empty(true)
+    .map(move |pair| match split_once(pair, key_value_delimeter.clone( )) {
+      Some((key, value)) => (key, Some(value)),
+      None => (pair, None),
+    })
+}
+
+/// Returns the part of s before the first occurrence of c (or the whole of s, if c isn't present).
+///
+/// ```
+/// assert_eq!("hello", lifetimes::until_char("hello world", ' '));
+/// assert_eq!("hello", lifetimes::until_char("hello", ' '));
+/// ```
+pub fn until_char(s: &str, c: char) -> &str {
+  // The returned segment borrows from s, and not from the StrSplit (which is a temporary here).
+  StrSplit::new(s, c)
+    .next( )
+    .expect("StrSplit always yields at least 1 segment")
+}
+
+/// Returns the part of s before the first occurrence of pattern (or the whole of s, if pattern
+/// isn't present).
+///
+/// ```
+/// assert_eq!("key", lifetimes::until_str("key: value", ": "));
+/// assert_eq!("key", lifetimes::until_str("key", ": "));
+/// ```
+pub fn until_str<'s>(s: &'s str, pattern: &str) -> &'s str {
+  StrSplit::new(s, pattern)
+    .next( )
+    .expect("StrSplit always yields at least 1 segment")
+}
+
+// Finds the next match in the haystack, at or after start (which is where the previous match, if
+// any, ended). Just like std's split and the regex crate, an empty match right where the previous
+// match ended is skipped, and the search continues from the next character (which becomes part of
+// the next segment). Otherwise, an empty delimeter would never make progress, and a delimeter like
+// \s* would match again (emptily) right after each of its matches.
+fn find_next_match<D>(
+  delimeter: &D,
+  haystack: &str,
+  start: usize,
+  after_match: bool,
+) -> Option<(usize, usize)>
+  where
+    D: Delimeter
+{
+  let found = delimeter.find_next_at(haystack, start)?;
+  if !after_match || found != (start, start) {
+    return Some(found);
+  }
+
+  let c = haystack[start..].chars( ).next( )?;
+  delimeter.find_next_at(haystack, start + c.len_utf8( ))
+}
+
+// Like StrSplit, but each segment includes the delimeter which terminates it. Just like std's
+// split_inclusive, there's no trailing empty segment when the haystack ends with a delimeter.
+#[derive(Debug, Clone)]
+pub struct StrSplitInclusive<'haystack, D> {
+  haystack: &'haystack str,
+  delimeter: D,
+  position: Option<usize>,
+  after_match: bool,
+}
+
+impl<'haystack, D> StrSplitInclusive<'haystack, D> {
+  pub fn new(haystack: &'haystack str, delimeter: D) -> Self {
+    Self {
+      haystack,
+      delimeter,
+      position: Some(0),
+      after_match: false,
+    }
+  }
+}
+
+impl<'haystack, D> Iterator for StrSplitInclusive<'haystack, D>
+  where
+    D: Delimeter
+{
+  type Item = &'haystack str;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    let start = self.position?;
+
+    if let Some((_, delim_end)) =
+      find_next_match(&self.delimeter, self.haystack, start, self.after_match)
+    {
+      self.position = Some(delim_end);
+      self.after_match = true;
+      Some(&self.haystack[start..delim_end])
+    } else {
+      self.position = None;
+      Some(&self.haystack[start..]).filter(|remainder| !remainder.is_empty( ))
+    }
+  }
+}
+
+impl<'haystack, D> FusedIterator for StrSplitInclusive<'haystack, D>
+  where
+    D: Delimeter
+{ }
+
+// Same as StrSplit, but over a byte slice (which needn't be valid UTF-8).
+#[derive(Debug, Clone)]
+pub struct BytesSplit<'haystack, D> {
+  remainder: Option<&'haystack [u8]>,
+  delimeter: D,
+  after_empty_match: bool,
+}
+
+impl<'haystack, D> BytesSplit<'haystack, D> {
+  pub fn new(haystack: &'haystack [u8], delimeter: D) -> Self {
+    Self {
+      remainder: Some(haystack),
+      delimeter,
+      after_empty_match: false,
+    }
+  }
+
+  pub fn remainder(&self) -> Option<&'haystack [u8]> {
+    self.remainder
+  }
+}
+
+pub trait ByteDelimeter {
+  fn find_next(&self, s: &[u8]) -> Option<(usize, usize)>;
+}
+
+impl ByteDelimeter for &[u8] {
+  fn find_next(&self, s: &[u8]) -> Option<(usize, usize)> {
+    if self.is_empty( ) {
+      return Some((0, 0));
+    }
+
+    s.windows(self.len( ))
+      .position(|window| window == *self)
+      .map(|start| (start, start + self.len( )))
+  }
+}
+
+// So that byte string literals (like b"\r\n") can be used directly.
+impl<const N: usize> ByteDelimeter for &[u8; N] {
+  fn find_next(&self, s: &[u8]) -> Option<(usize, usize)> {
+    self.as_slice( ).find_next(s)
+  }
+}
+
+impl ByteDelimeter for u8 {
+  fn find_next(&self, s: &[u8]) -> Option<(usize, usize)> {
+    s.iter( ).position(|byte| byte == self).map(|start| (start, start + 1))
+  }
+}
+
+// Matches any single byte for which the closure returns true.
+impl<F> ByteDelimeter for F
+  where
+    F: Fn(u8) -> bool
+{
+  fn find_next(&self, s: &[u8]) -> Option<(usize, usize)> {
+    s.iter( ).position(|byte| self(*byte)).map(|start| (start, start + 1))
+  }
+}
+
+impl<'haystack, D> Iterator for BytesSplit<'haystack, D>
+  where
+    D: ByteDelimeter
+{
+  type Item = &'haystack [u8];
+
+  fn next(&mut self) -> Option<Self::Item> {
+    let remainder = self.remainder?;
+
+    if let Some((delim_start, delim_end)) =
+      find_next_byte_match(&self.delimeter, remainder, self.after_empty_match)
+    {
+      let until_delimeter = &remainder[..delim_start];
+      self.remainder = Some(&remainder[delim_end..]);
+      self.after_empty_match = delim_start == delim_end;
+      Some(until_delimeter)
+    } else {
+      self.remainder.take( )
+    }
+  }
+}
+
+impl<'haystack, D> FusedIterator for BytesSplit<'haystack, D>
+  where
+    D: ByteDelimeter
+{ }
+
+// Like for StrSplit, an empty match can't be right at the start of the remainder again. But here,
+// every byte (and not character) boundary is a match.
+fn find_next_byte_match<D>(
+  delimeter: &D,
+  remainder: &[u8],
+  after_empty_match: bool,
+) -> Option<(usize, usize)>
+  where
+    D: ByteDelimeter
+{
+  let search_from = match (after_empty_match, remainder.is_empty( )) {
+    (true, true) => return None,
+    (true, false) => 1,
+    (false, _) => 0,
+  };
+
+  let (delim_start, delim_end) = delimeter.find_next(&remainder[search_from..])?;
+  Some((search_from + delim_start, search_from + delim_end))
+}
+
+// Same as StrSplitInclusive, but over a byte slice.
+// NOTE - There's no reverse (RStrSplit like) byte splitter, since ByteDelimeter can only search
+// forwards (it has no find_last).
+#[derive(Debug, Clone)]
+pub struct BytesSplitInclusive<'haystack, D> {
+  remainder: Option<&'haystack [u8]>,
+  delimeter: D,
+  after_empty_match: bool,
+}
+
+impl<'haystack, D> BytesSplitInclusive<'haystack, D> {
+  pub fn new(haystack: &'haystack [u8], delimeter: D) -> Self {
+    Self {
+      remainder: Some(haystack),
+      delimeter,
+      after_empty_match: false,
+    }
+  }
+}
+
+impl<'haystack, D> Iterator for BytesSplitInclusive<'haystack, D>
+  where
+    D: ByteDelimeter
+{
+  type Item = &'haystack [u8];
+
+  fn next(&mut self) -> Option<Self::Item> {
+    let remainder = self.remainder?;
+
+    if let Some((delim_start, delim_end)) =
+      find_next_byte_match(&self.delimeter, remainder, self.after_empty_match)
+    {
+      let until_delimeter_end = &remainder[..delim_end];
+      self.remainder = Some(&remainder[delim_end..]);
+      self.after_empty_match = delim_start == delim_end;
+      Some(until_delimeter_end)
+    } else {
+      self.remainder.take( ).filter(|remainder| !remainder.is_empty( ))
+    }
+  }
+}
+
+impl<'haystack, D> FusedIterator for BytesSplitInclusive<'haystack, D>
+  where
+    D: ByteDelimeter
+{ }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn case_CharDelimeters( ) {
+    let haystack = "a,b;c\td,,e";
+
+    let segments: Vec<_> = StrSplit::new(haystack, [',', ';', '\t']).collect( );
+    assert_eq!(vec!["a", "b", "c", "d", "", "e"], segments);
+
+    let candidates: &[char] = &[',', ';', '\t'];
+    let expected: Vec<_> = haystack.split(|c| candidates.contains(&c)).collect( );
+    assert_eq!(expected, StrSplit::new(haystack, candidates).collect::<Vec<_>>( ));
+
+    // Multibyte candidates.
+    let segments: Vec<_> = StrSplit::new("αβγ→δ€ε", ['→', '€']).collect( );
+    assert_eq!(vec!["αβγ", "δ", "ε"], segments);
+
+    // An empty list of candidates never matches.
+    let segments: Vec<_> = StrSplit::new("a,b", [ ]).collect( );
+    assert_eq!(vec!["a,b"], segments);
+  }
+
+  #[test]
+  fn case_EmptyDelimeter( ) {
+    for haystack in ["abc", "αβ€", ""] {
+      let expected: Vec<_> = haystack.split("").collect( );
+      assert_eq!(expected, StrSplit::new(haystack, "").collect::<Vec<_>>( ));
+    }
+
+    assert_eq!(vec!["", "a", "b", "c", ""], StrSplit::new("abc", "").collect::<Vec<_>>( ));
+  }
+
+  #[test]
+  fn case_Limit( ) {
+    for haystack in ["key=value=with=equals", "key=value", "key", "", "=", "a=="] {
+      for n in 0..5 {
+        let expected: Vec<_> = haystack.splitn(n, '=').collect( );
+        assert_eq!(expected, StrSplit::new(haystack, "=").limit(n).collect::<Vec<_>>( ));
+      }
+
+      assert_eq!(haystack.split_once('='), split_once(haystack, "="));
+    }
+
+    assert_eq!(vec!["key", "value=with=equals"],
+               StrSplit::new("key=value=with=equals", "=").limit(2).collect::<Vec<_>>( ));
+    assert_eq!(Some(("key", "value=with=equals")), split_once("key=value=with=equals", "="));
+    assert_eq!(None, split_once("key", "="));
+  }
+
+  #[test]
+  fn case_SplitInclusive( ) {
+    let lines: Vec<_> = StrSplitInclusive::new("a\r\nb\nc", "\n").collect( );
+    assert_eq!(vec!["a\r\n", "b\n", "c"], lines);
+
+    // No trailing empty segment.
+    let lines: Vec<_> = StrSplitInclusive::new("a\nb\n", "\n").collect( );
+    assert_eq!(vec!["a\n", "b\n"], lines);
+
+    for haystack in ["a\r\nb\nc", "a\nb\n", "\n\n", "", "abc"] {
+      for delimeter in ["\n", "\r\n", ""] {
+        let expected: Vec<_> = haystack.split_inclusive(delimeter).collect( );
+        assert_eq!(expected, StrSplitInclusive::new(haystack, delimeter).collect::<Vec<_>>( ));
+      }
+    }
+  }
+
+  #[test]
+  fn case_Remainder( ) {
+    let mut segments = StrSplit::new("a b c d e", " ");
+    assert_eq!(Some("a"), segments.next( ));
+    assert_eq!(Some("b"), segments.next( ));
+
+    let remainder = segments.remainder( );
+    assert_eq!(Some("c d e"), remainder);
+    assert!(!segments.is_finished( ));
+
+    segments.by_ref( ).for_each(drop);
+    assert_eq!(None, segments.remainder( ));
+    assert!(segments.is_finished( ));
+
+    // The remainder outlives the StrSplit.
+    let remainder = {
+      let mut segments = StrSplit::new("header body", " ");
+      segments.next( );
+      segments.into_remainder( )
+    };
+    assert_eq!(Some("body"), remainder);
+  }
+
+  #[test]
+  fn case_Until( ) {
+    assert_eq!("hello", until_char("hello world", ' '));
+    assert_eq!("hello", until_char("hello", ' '));
+    assert_eq!("", until_char("", ' '));
+    assert_eq!("", until_char(" hello", ' '));
+    assert_eq!("αβ", until_char("αβ→γ", '→'));
+
+    assert_eq!("key", until_str("key: value", ": "));
+    assert_eq!("key", until_str("key", ": "));
+    assert_eq!("", until_str("", ": "));
+    assert_eq!("", until_str(": value", ": "));
+
+    // The result is tied to the haystack, and not to the (temporary) pattern.
+    let haystack = String::from("key=value");
+    let key = {
+      let pattern = String::from("=");
+      until_str(&haystack, &pattern)
+    };
+    assert_eq!("key", key);
+  }
+
+  #[test]
+  fn case_AsciiCaseInsensitive( ) {
+    let haystack = "part1--BOUNDARY--part2--Boundary--part3";
+    let segments: Vec<_> = StrSplit::new(haystack, AsciiCaseInsensitive("--boundary--")).collect( );
+    assert_eq!(vec!["part1", "part2", "part3"], segments);
+
+    // The span is that of the actual bytes in the haystack.
+    assert_eq!(Some((4, 6)), AsciiCaseInsensitive("xY").find_next("αβXy"));
+
+    let segments: Vec<_> =
+      StrSplit::new("αβ AND γδ and ε", AsciiCaseInsensitive(" and ")).collect( );
+    assert_eq!(vec!["αβ", "γδ", "ε"], segments);
+
+    // Non-ASCII characters need to match exactly.
+    assert_eq!(None, AsciiCaseInsensitive("Α").find_next("α"));
+    assert_eq!(None, AsciiCaseInsensitive("boundary").find_next("no match here"));
+  }
+
+  #[test]
+  fn case_CaseInsensitive( ) {
+    let segments: Vec<_> = StrSplit::new("aΣΤΟb στο c", CaseInsensitive("στο")).collect( );
+    assert_eq!(vec!["a", "b ", " c"], segments);
+
+    assert_eq!(Some((2, 10)), CaseInsensitive("Boundary").find_next("--BOUNDARY--"));
+    assert_eq!(None, CaseInsensitive("boundary").find_next("bound"));
+    assert_eq!(None, CaseInsensitive("ß").find_next("ss"));
+  }
+
+  #[test]
+  fn case_BytesSplit( ) {
+    let records: Vec<_> = BytesSplit::new(b"GET /\r\nHost: a\r\n\r\n", b"\r\n").collect( );
+    assert_eq!(vec![&b"GET /"[..], b"Host: a", b"", b""], records);
+
+    let fields: Vec<_> = BytesSplit::new(b"a\0bc\0\0d", 0u8).collect( );
+    assert_eq!(vec![&b"a"[..], b"bc", b"", b"d"], fields);
+
+    let fields: Vec<_> = BytesSplit::new(b"a1b22c", |byte: u8| byte.is_ascii_digit( )).collect( );
+    assert_eq!(vec![&b"a"[..], b"b", b"", b"c"], fields);
+
+    // The delimeter straddles bytes, which on their own, aren't valid UTF-8.
+    let haystack = [0xE2, 0x82, 0xFF, 0xFE, 0xAC, 0x41];
+    let delimeter: &[u8] = &[0x82, 0xFF, 0xFE];
+    let segments: Vec<_> = BytesSplit::new(&haystack, delimeter).collect( );
+    assert_eq!(vec![&[0xE2][..], &[0xAC, 0x41]], segments);
+
+    let segments: Vec<_> = BytesSplit::new(b"ab", &b""[..]).collect( );
+    assert_eq!(vec![&b""[..], b"a", b"b", b""], segments);
+  }
+
+  #[test]
+  fn case_BytesSplitInclusive( ) {
+    let lines: Vec<_> = BytesSplitInclusive::new(b"a\r\nb\r\n", b"\r\n").collect( );
+    assert_eq!(vec![&b"a\r\n"[..], b"b\r\n"], lines);
+
+    for haystack in [&b"a,b"[..], b",a,,b,", b",", b"", b"\xFF,\xFE"] {
+      let expected: Vec<_> = haystack.split_inclusive(|byte| *byte == b',').collect( );
+      assert_eq!(expected, BytesSplitInclusive::new(haystack, b',').collect::<Vec<_>>( ));
+    }
+  }
+
+  #[test]
+  fn case_SkipEmptyAndTrim( ) {
+    let haystack = "a,,b, ,  c ,";
+
+    let segments: Vec<_> = StrSplit::new(haystack, ",").skip_empty(false).collect( );
+    assert_eq!(vec!["a", "", "b", " ", "  c ", ""], segments);
+
+    let segments: Vec<_> = StrSplit::new(haystack, ",").skip_empty(true).collect( );
+    assert_eq!(vec!["a", "b", " ", "  c "], segments);
+
+    let segments: Vec<_> = StrSplit::new(haystack, ",").trim(true).collect( );
+    assert_eq!(vec!["a", "", "b", "", "c", ""], segments);
+
+    let segments: Vec<_> = StrSplit::new(haystack, ",").skip_empty(true).trim(true).collect( );
+    assert_eq!(vec!["a", "b", "c"], segments);
+
+    // The limit counts only the segments which are yielded. And the last segment is the rest of the
+    // haystack (only trimmed), just like without skip_empty.
+    let segments: Vec<_> = StrSplit::new(haystack, ",").skip_empty(true).trim(true).limit(2)
+                                                      .collect( );
+    assert_eq!(vec!["a", ",b, ,  c ,"], segments);
+
+    let segments: Vec<_> = StrSplit::new(" , a, b", ",").skip_empty(true).trim(true).limit(2)
+                                                      .collect( );
+    assert_eq!(vec!["a", "b"], segments);
+  }
+
+  #[test]
+  fn case_RSplit( ) {
+    let haystacks = ["a,b,,c", ",a,", "", "no delimeter", "αβ,γ", "abc"];
+
+    for haystack in haystacks {
+      let mut forward: Vec<_> = StrSplit::new(haystack, ",").collect( );
+      forward.reverse( );
+      assert_eq!(forward, RStrSplit::new(haystack, ",").collect::<Vec<_>>( ));
+      assert_eq!(forward, RStrSplit::new(haystack, ',').collect::<Vec<_>>( ));
+      assert_eq!(forward, RStrSplit::new(haystack, [',', ';']).collect::<Vec<_>>( ));
+
+      // Goes through the default find_last.
+      assert_eq!(forward, RStrSplit::new(haystack, AsciiCaseInsensitive(",")).collect::<Vec<_>>( ));
+
+      let expected: Vec<_> = haystack.rsplit("").collect( );
+      assert_eq!(expected, RStrSplit::new(haystack, "").collect::<Vec<_>>( ));
+      assert_eq!(expected, RStrSplit::new(haystack, CaseInsensitive("")).collect::<Vec<_>>( ));
+    }
+
+    assert_eq!(Some(("archive.tar", "gz")), rsplit_once("archive.tar.gz", '.'));
+    assert_eq!(Some(("", "bashrc")), rsplit_once(".bashrc", "."));
+    assert_eq!(None, rsplit_once("Makefile", '.'));
+  }
+
+  #[test]
+  fn case_OwnedDelimeters( ) {
+    let separator = ["<", "|", ">"].concat( );
+    let haystack = "a<|>b<|>c";
+    let expected = vec!["a", "b", "c"];
+
+    assert_eq!(expected, StrSplit::new(haystack, separator.clone( )).collect::<Vec<_>>( ));
+    assert_eq!(expected, StrSplit::new(haystack, Box::<str>::from(separator.as_str( )))
+                           .collect::<Vec<_>>( ));
+    assert_eq!(expected, StrSplit::new(haystack, Rc::<str>::from(separator.as_str( )))
+                           .collect::<Vec<_>>( ));
+    assert_eq!(expected, StrSplit::new(haystack, Arc::<str>::from(separator.as_str( )))
+                           .collect::<Vec<_>>( ));
+  }
+
+  #[test]
+  fn case_OwnedStrSplit( ) {
+    let mut segments = {
+      let haystack = String::from("a,b,,c");
+      OwnedStrSplit::new(haystack, String::from(","))
+    };
+
+    let borrowed: Vec<_> = StrSplit::new("a,b,,c", ",").collect( );
+    let mut owned = Vec::new( );
+    while let Some(segment) = segments.next_segment( ) {
+      owned.push(segment.to_owned( ));
+    }
+    assert_eq!(borrowed, owned);
+    assert_eq!(None, segments.next( ));
+
+    let segments = OwnedStrSplit::new(String::from("ab,c"), ',');
+    let haystack = segments.haystack( ).to_owned( );
+    let spans: Vec<_> = segments.collect( );
+    assert_eq!(vec![0..2, 3..4], spans);
+    let segments: Vec<_> = spans.into_iter( ).map(|span| &haystack[span]).collect( );
+    assert_eq!(vec!["ab", "c"], segments);
+  }
+
+  #[test]
+  fn case_MatchIndices( ) {
+    for haystack in ["a,b,,c", ",", "", "αβ,γ,δ", "no delimeter"] {
+      let expected: Vec<_> = haystack.match_indices(",").collect( );
+      assert_eq!(expected, MatchIndices::new(haystack, ",").collect::<Vec<_>>( ));
+    }
+
+    let matches: Vec<_> = MatchIndices::new("αβ→γ€δ", ['→', '€']).collect( );
+    assert_eq!(vec![(4, "→"), (9, "€")], matches);
+
+    // Occurrences don't overlap, just like with std.
+    let matches: Vec<_> = MatchIndices::new("aaaaa", "aa").collect( );
+    assert_eq!(vec![(0, "aa"), (2, "aa")], matches);
+    assert_eq!("aaaaa".match_indices("aa").collect::<Vec<_>>( ), matches);
+
+    let expected: Vec<_> = "αβ".match_indices("").collect( );
+    assert_eq!(expected, MatchIndices::new("αβ", "").collect::<Vec<_>>( ));
+  }
+
+  #[test]
+  fn case_SplitWithSpans( ) {
+    let haystack = "αβ,γ,,δ";
+
+    let segments: Vec<_> = SplitWithSpans::new(haystack, ",").collect( );
+    assert_eq!(vec![(0..4, "αβ"), (5..7, "γ"), (8..8, ""), (9..11, "δ")], segments);
+
+    for (span, segment) in segments {
+      assert_eq!(segment, &haystack[span]);
+    }
+  }
+
+  #[cfg(feature = "regex")]
+  #[test]
+  fn case_RegexDelimeter( ) {
+    use regex::Regex;
+
+    let whitespace = Regex::new(r"\s+").unwrap( );
+    let line = "2024-01-01  INFO\t service   started ";
+
+    let segments: Vec<_> = StrSplit::new(line, &whitespace).collect( );
+    assert_eq!(vec!["2024-01-01", "INFO", "service", "started", ""], segments);
+    assert_eq!(whitespace.split(line).collect::<Vec<_>>( ), segments);
+
+    let segments: Vec<_> = StrSplit::new(line, &whitespace).limit(2).collect( );
+    assert_eq!(whitespace.splitn(line, 2).collect::<Vec<_>>( ), segments);
+
+    let segments: Vec<_> = StrSplitInclusive::new("a b  c", &whitespace).collect( );
+    assert_eq!(vec!["a ", "b  ", "c"], segments);
+
+    let mut segments: Vec<_> = RStrSplit::new(line, &whitespace).collect( );
+    segments.reverse( );
+    assert_eq!(whitespace.split(line).collect::<Vec<_>>( ), segments);
+
+    // Zero width matches split at every character boundary, just like an empty &str.
+    let empty = Regex::new("").unwrap( );
+    for haystack in ["abc", "αβ", ""] {
+      let expected: Vec<_> = empty.split(haystack).collect( );
+      assert_eq!(expected, StrSplit::new(haystack, &empty).collect::<Vec<_>>( ));
+    }
+
+    // $ only matches at the end of the haystack (which is also the end of each remainder).
+    let trailing_commas = Regex::new(",+$").unwrap( );
+    let segments: Vec<_> = StrSplit::new("a,b,,,", trailing_commas).collect( );
+    assert_eq!(vec!["a,b", ""], segments);
+
+    // The regex sees the whole haystack, so ^ only matches at its start (and not at the start of
+    // each remainder).
+    let leading_dash = Regex::new("^-").unwrap( );
+    let segments: Vec<_> = StrSplit::new("--a-b", leading_dash).collect( );
+    assert_eq!(vec!["", "-a-b"], segments);
+  }
+
+  #[cfg(feature = "regex")]
+  #[test]
+  fn case_RegexMatchesRegexSplit( ) {
+    use regex::Regex;
+
+    // Patterns which can match zero characters, and ones whose matches depend on what comes before
+    // the remainder.
+    let patterns = [r"\s*", "a*", "a|", r"\b", "^a", r"\s+", ""];
+    let haystacks = ["", "a", "aaa", "a b", " a  b ", "ba ab", "αa β", "a,b"];
+
+    for pattern in patterns {
+      let regex = Regex::new(pattern).unwrap( );
+
+      for haystack in haystacks {
+        let expected: Vec<_> = regex.split(haystack).collect( );
+        assert_eq!(
+          expected,
+          StrSplit::new(haystack, &regex).collect::<Vec<_>>( ),
+          "{pattern:?} on {haystack:?}"
+        );
+
+        let expected: Vec<_> =
+          regex.find_iter(haystack).map(|found| (found.start( ), found.as_str( ))).collect( );
+        assert_eq!(
+          expected,
+          MatchIndices::new(haystack, &regex).collect::<Vec<_>>( ),
+          "{pattern:?} on {haystack:?}"
+        );
+      }
+    }
+  }
+
+  const TRICKY_HAYSTACKS: [&str; 10] = [
+    "", " ", ",", ",,", "a", "a,b", ",a,b,", "  a  b  ", "a,,b,\t\n", " \u{3000}α\u{a0}β,",
+  ];
+
+  #[test]
+  fn case_Whitespace( ) {
+    for haystack in TRICKY_HAYSTACKS {
+      let expected: Vec<_> = haystack.split_whitespace( ).collect( );
+      assert_eq!(expected, StrSplit::whitespace(haystack).collect::<Vec<_>>( ));
+    }
+  }
+
+  #[test]
+  fn case_Terminator( ) {
+    for haystack in TRICKY_HAYSTACKS {
+      let expected: Vec<_> = haystack.split_terminator(',').collect( );
+      assert_eq!(expected, StrSplit::terminator(haystack, ',').collect::<Vec<_>>( ));
+    }
+  }
+
+  #[test]
+  fn case_Clone( ) {
+    let mut segments = StrSplit::new("a,b,c,d", ",");
+    assert_eq!(Some("a"), segments.next( ));
+
+    let mut cloned = segments.clone( );
+    assert_eq!(Some("b"), cloned.next( ));
+    assert_eq!(Some("c"), cloned.next( ));
+
+    // Both the copies continue independently.
+    assert_eq!(vec!["b", "c", "d"], segments.collect::<Vec<_>>( ));
+    assert_eq!(vec!["d"], cloned.collect::<Vec<_>>( ));
+  }
+
+  #[test]
+  fn case_StaysExhausted( ) {
+    let mut segments = StrSplit::new("a,b", ",");
+    segments.by_ref( ).for_each(drop);
+
+    for _ in 0..5 {
+      assert_eq!(None, segments.next( ));
+    }
+
+    let mut segments = StrSplit::new("a,b", ",").limit(1);
+    segments.next( );
+    for _ in 0..5 {
+      assert_eq!(None, segments.next( ));
+    }
+  }
+
+  const CSV: QuotedDelimeter = QuotedDelimeter { delim: ',', quote: '"', escape: Some('\\') };
+
+  // A tiny PRNG, for generating haystacks.
+  fn xorshift(state: &mut u64) -> u64 {
+    *state ^= *state << 13;
+    *state ^= *state >> 7;
+    *state ^= *state << 17;
+    *state
+  }
+
+  #[test]
+  fn case_QuotedDelimeter( ) {
+    let fields: Vec<_> = StrSplit::new(r#"a,"b,c",d"#, CSV).collect( );
+    assert_eq!(vec!["a", r#""b,c""#, "d"], fields);
+
+    let fields: Vec<_> = StrSplit::new(r#"a,"say \"hi\", bye",\,d"#, CSV).collect( );
+    assert_eq!(vec!["a", r#""say \"hi\", bye""#, r#"\,d"#], fields);
+
+    let fields: Vec<_> = StrSplit::new(r#""a","b,","",c"#, CSV).collect( );
+    assert_eq!(vec![r#""a""#, r#""b,""#, r#""""#, "c"], fields);
+
+    // Doubled quotes, without an escape character.
+    let csv = QuotedDelimeter { escape: None, ..CSV };
+    let fields: Vec<_> = StrSplit::new(r#""a ""b"", c",d"#, csv).collect( );
+    assert_eq!(vec![r#""a ""b"", c""#, "d"], fields);
+
+    // Unterminated quote.
+    let fields: Vec<_> = StrSplit::new(r#"a,"b,c,d"#, CSV).collect( );
+    assert_eq!(vec!["a", r#""b,c,d"#], fields);
+  }
+
+  #[test]
+  fn case_QuotedDelimeterMatchesReference( ) {
+    fn reference(line: &str) -> Vec<String> {
+      let (mut fields, mut field) = (Vec::new( ), String::new( ));
+      let (mut in_quotes, mut escaped) = (false, false);
+
+      for c in line.chars( ) {
+        match c {
+          _ if escaped => escaped = false,
+          '\\' => escaped = true,
+          '"' => in_quotes = !in_quotes,
+          ',' if !in_quotes => {
+            fields.push(std::mem::take(&mut field));
+            continue;
+          },
+          _ => { },
+        }
+        field.push(c);
+      }
+      fields.push(field);
+      fields
+    }
+
+    let mut state = 0x2545F4914F6CDD1D;
+    for _ in 0..2000 {
+      let line: String = (0..xorshift(&mut state) % 12)
+        .map(|_| ['a', 'β', ',', '"', '\\'][(xorshift(&mut state) % 5) as usize])
+        .collect( );
+
+      assert_eq!(reference(&line), StrSplit::new(&line, CSV).collect::<Vec<_>>( ), "{line:?}");
+    }
+  }
+
+  #[test]
+  fn case_Or( ) {
+    let segments: Vec<_> = StrSplit::new("a,b;c;;d,", ','.or(';')).collect( );
+    assert_eq!(vec!["a", "b", "c", "", "d", ""], segments);
+
+    let segments: Vec<_> = StrSplit::new("a,b--c;d", ','.or(';').or("--")).collect( );
+    assert_eq!(vec!["a", "b", "c", "d"], segments);
+
+    for haystack in TRICKY_HAYSTACKS.into_iter( ).chain(["a;b,c", ";;,", "α;β,γ"]) {
+      let expected: Vec<_> = StrSplit::new(haystack, [',', ';']).collect( );
+      assert_eq!(expected, StrSplit::new(haystack, ','.or(';')).collect::<Vec<_>>( ));
+
+      let expected: Vec<_> = RStrSplit::new(haystack, [',', ';']).collect( );
+      assert_eq!(expected, RStrSplit::new(haystack, ','.or(';')).collect::<Vec<_>>( ));
+    }
+
+    // On a tie, the longer match wins (regardless of the order).
+    let segments: Vec<_> = StrSplit::new("a--b-c", "-".or("--")).collect( );
+    assert_eq!(vec!["a", "b", "c"], segments);
+    let segments: Vec<_> = StrSplit::new("a--b-c", "--".or("-")).collect( );
+    assert_eq!(vec!["a", "b", "c"], segments);
+    assert_eq!(Some((1, 4)), "a-".or("a--").find_last("xa--"));
+
+    // But otherwise, the match which starts last wins.
+    assert_eq!(Some((2, 3)), "-".or("--").find_last("a--"));
+  }
+
+  #[test]
+  fn case_Before( ) {
+    let is_digit = |c: char| c.is_ascii_digit( );
+
+    let segments: Vec<_> = StrSplit::new("ab12c3", is_digit.before( )).collect( );
+    assert_eq!(vec!["ab", "1", "2c", "3"], segments);
+
+    let segments: Vec<_> = StrSplit::new("1a", is_digit.before( )).collect( );
+    assert_eq!(vec!["", "1a"], segments);
+
+    let segments: Vec<_> = StrSplit::new("no digits", is_digit.before( )).collect( );
+    assert_eq!(vec!["no digits"], segments);
+  }
+
+  #[test]
+  fn case_ParsePairs( ) {
+    let pairs: Vec<_> = parse_pairs("a=1&b=&c&d=4", '&', '=').collect( );
+    assert_eq!(vec![("a", Some("1")), ("b", Some("")), ("c", None), ("d", Some("4"))], pairs);
+
+    let pairs: Vec<_> = parse_pairs("&&a=1&&b=2=3&", "&", "=").collect( );
+    assert_eq!(vec![("a", Some("1")), ("b", Some("2=3"))], pairs);
+
+    // Percent encoded content is passed through, untouched.
+    let pairs: Vec<_> = parse_pairs("q=a%26b%3Dc&x%20y=1", '&', '=').collect( );
+    assert_eq!(vec![("q", Some("a%26b%3Dc")), ("x%20y", Some("1"))], pairs);
+
+    assert_eq!(None, parse_pairs("", '&', '=').next( ));
+  }
+
+  #[test]
+  fn case_ParsePairsOutliveTheIterators( ) {
+    let query = String::from("user=ferris&lang=rust");
+
+    let pairs: Vec<(&str, Option<&str>)> = {
+      // The delimeters and the iterator are dropped at the end of this block.
+      let pair_delimeter = String::from("&");
+      let key_value_delimeter = String::from("=");
+      parse_pairs(&query, pair_delimeter, key_value_delimeter).collect( )
+    };
+
+    assert_eq!(vec![("user", Some("ferris")), ("lang", Some("rust"))], pairs);
+  }
+
+  #[cfg(feature = "memchr")]
+  #[test]
+  fn case_ByteDelimeterMatchesGeneric( ) {
+    let mut state = 0x853C49E6748FEA9B;
+
+    for alphabet in [&['a', 'b', ',', ' '][..], &['a', 'α', '€', ',', '𝄞'][..]] {
+      for _ in 0..1000 {
+        let haystack: String = (0..xorshift(&mut state) % 20)
+          .map(|_| alphabet[(xorshift(&mut state) % alphabet.len( ) as u64) as usize])
+          .collect( );
+
+        let expected: Vec<_> = StrSplit::new(&haystack, ",").collect( );
+        assert_eq!(expected, StrSplit::new(&haystack, Byte::new(b',')).collect::<Vec<_>>( ));
+
+        let expected: Vec<_> = RStrSplit::new(&haystack, ",").collect( );
+        assert_eq!(expected, RStrSplit::new(&haystack, Byte::new(b',')).collect::<Vec<_>>( ));
+      }
+    }
+  }
+
+  #[cfg(feature = "memchr")]
+  #[test]
+  #[should_panic(expected = "the byte delimeter must be ASCII")]
+  fn case_NonAsciiByteDelimeter( ) {
+    Byte::new(0xE2);
+  }
+}
@@ -19,8 +19,11 @@
 */
 
 use proc_macro::TokenStream;
-use quote::quote;
-use syn::{ parse_macro_input, DeriveInput, Ident, Data, Fields, Type, PathArguments, GenericArgument };
+use quote::{quote, ToTokens};
+use syn::{
+  parse_macro_input, DeriveInput, Ident, Data, Field, Fields, Type, PathArguments, GenericArgument,
+  Meta, MetaNameValue, Expr, ExprLit, Lit
+};
 
 #[proc_macro_derive(Builder, attributes(builder))]
 pub fn derive(input: TokenStream) -> TokenStream {
@@ -32,23 +35,49 @@ pub fn derive(input: TokenStream) -> TokenStream {
   let builderStructName= format!("{}Builder", structName);
   let builderStructIdent= Ident::new(&builderStructName, structName.span( ));
 
+  // The builder has the same generics as the struct. Every generic parameter is used by some
+  // field of the struct, so it's used by the builder as well.
+  let generics= &ast.generics;
+  let (implGenerics, typeGenerics, whereClause)= generics.split_for_impl( );
+
   let fields= match ast.data {
     Data::Struct(structData) => {
 
       match structData.fields {
         Fields::Named(namedFields) => namedFields.named,
 
-        _ => unimplemented!( )
+        _ => return compileError(&structName, "Builder needs a struct with named fields")
       }
     },
-    _ => unimplemented!( )
+    _ => return compileError(&structName, "Builder can only be derived for structs")
   };
 
-  let optionizedFields= fields.iter( ).map(|field| {
+  // Validating the #[builder] attributes of all the fields up front, so that every malformed one
+  // gets reported (and not just the first).
+  let mut eachSetterNames= Vec::new( );
+  let mut errors: Option<syn::Error>= None;
+
+  for field in &fields {
+    match eachSetterName(field) {
+      Ok(eachSetterName) => eachSetterNames.push(eachSetterName),
+
+      Err(error) => match errors {
+        Some(ref mut errors) => errors.combine(error),
+        None => errors= Some(error)
+      }
+    }
+  }
+
+  if let Some(errors)= errors {
+    return errors.to_compile_error( ).into( )}
+
+  // Fields with #[builder(each = "...")] are Vecs, which start out empty and are pushed to one item
+  // at a time. So, they aren't wrapped in an Option.
+  let optionizedFields= fields.iter( ).zip(&eachSetterNames).map(|(field, eachSetterName)| {
     let fieldIdent= &field.ident;
     let fieldType= &field.ty;
 
-    if isOptionType(fieldType) {
+    if isOptionType(fieldType) || eachSetterName.is_some( ) {
       quote! { #fieldIdent: #fieldType }
     }
     else {
@@ -56,17 +85,34 @@ pub fn derive(input: TokenStream) -> TokenStream {
     }
   });
 
-  let initFields= fields.iter( ).map(|field| {
+  let initFields= fields.iter( ).zip(&eachSetterNames).map(|(field, eachSetterName)| {
     let fieldIdent= &field.ident;
 
-    quote! { #fieldIdent: None }
+    if eachSetterName.is_some( ) {
+      quote! { #fieldIdent: std::vec::Vec::new( ) }
+    }
+    else {
+      quote! { #fieldIdent: None }
+    }
   });
 
-  let builderStructMethods= fields.iter( ).map(|field| {
+  let builderStructMethods= fields.iter( ).zip(&eachSetterNames).map(|(field, eachSetterName)| {
     let fieldIdent= &field.ident;
     let fieldType= &field.ty;
 
-    if let Some(argType)= unwrapOptionType(fieldType) {
+    if let Some(eachSetterName)= eachSetterName {
+      // The each setter takes precedence, if the names collide.
+      if fieldIdent.as_ref( ) == Some(eachSetterName) {
+        return quote!( )}
+
+      quote! {
+        pub fn #fieldIdent(&mut self, #fieldIdent: #fieldType) -> &mut Self {
+          self.#fieldIdent= #fieldIdent;
+          self
+        }
+      }
+    }
+    else if let Some(argType)= unwrapOptionType(fieldType) {
       quote! {
         pub fn #fieldIdent(&mut self, #fieldIdent: #argType) -> &mut Self {
           self.#fieldIdent= Some(#fieldIdent);
@@ -84,28 +130,26 @@ pub fn derive(input: TokenStream) -> TokenStream {
     }
   });
 
-  let builderStructEachMethods= fields.iter( ).filter_map(|field| {
-
-    for attribute in &field.attrs {
-      let segments= &attribute.meta.path( ).segments;
-
-      if (
-        segments.len( ) != 1 ||
-        segments.first( ).unwrap( ).ident != "builder"
-
-      ) { continue }
-
-      return Some(quote!(/* */))
-    }
+  let builderStructEachMethods= fields.iter( ).zip(&eachSetterNames).filter_map(|fieldAndName| {
+    let (field, eachSetterName)= fieldAndName;
+    let fieldIdent= &field.ident;
+    let eachSetterName= eachSetterName.as_ref( )?;
+    let itemType= unwrapVecType(&field.ty)?;
 
-    None
+    Some(quote! {
+      pub fn #eachSetterName(&mut self, item: #itemType) -> &mut Self {
+        self.#fieldIdent.push(item);
+        self
+      }
+    })
   });
 
-  let buildMethodFields= fields.iter( ).map(|field| {
+  let buildMethodFields= fields.iter( ).zip(&eachSetterNames).map(|(field, eachSetterName)| {
     let fieldIdent= &field.ident;
     let fieldType= &field.ty;
 
-    if isOptionType(fieldType) {
+    // Each fields default to being empty, when nothing has been set.
+    if isOptionType(fieldType) || eachSetterName.is_some( ) {
       quote! { #fieldIdent: self.#fieldIdent.clone( ) }
     }
     else {
@@ -118,24 +162,25 @@ pub fn derive(input: TokenStream) -> TokenStream {
 
   quote! {
 
-    struct #builderStructIdent {
+    // Unlike implGenerics, generics keeps the defaults of the type parameters.
+    struct #builderStructIdent #generics #whereClause {
       #(#optionizedFields,)*
     }
 
-    impl #structName {
-      pub fn builder( ) -> #builderStructIdent {
+    impl #implGenerics #structName #typeGenerics #whereClause {
+      pub fn builder( ) -> #builderStructIdent #typeGenerics {
         #builderStructIdent {
           #(#initFields,)*
         }
       }
     }
 
-    impl #builderStructIdent {
+    impl #implGenerics #builderStructIdent #typeGenerics #whereClause {
 
       #(#builderStructMethods)*
       #(#builderStructEachMethods)*
 
-      pub fn build(&self) -> Result<#structName, Box<dyn std::error::Error>> {
+      pub fn build(&self) -> Result<#structName #typeGenerics, Box<dyn std::error::Error>> {
         Ok(#structName {
           #(#buildMethodFields,)*
         })
@@ -156,10 +201,19 @@ fn isOptionType(_type: &Type) -> bool {
 }
 
 fn unwrapOptionType(_type: &Type) -> Option<&Type> {
+  unwrapGenericType(_type, "Option")
+}
+
+fn unwrapVecType(_type: &Type) -> Option<&Type> {
+  unwrapGenericType(_type, "Vec")
+}
+
+// Returns T, if the type is wrapper<T>.
+fn unwrapGenericType<'a>(_type: &'a Type, wrapper: &str) -> Option<&'a Type> {
   if let Type::Path(ref path)= _type {
     if (
       path.path.segments.len( ) != 1 ||
-      path.path.segments.iter( ).last( ).unwrap( ).ident != "Option"
+      path.path.segments.iter( ).last( ).unwrap( ).ident != wrapper
 
     ) { return None }
 
@@ -173,4 +227,38 @@ fn unwrapOptionType(_type: &Type) -> Option<&Type> {
   }
 
   None
-}
\ No newline at end of file
+}
+
+// Returns the name of the each setter, if the field has #[builder(each = "...")]. Anything else
+// inside #[builder(...)], or using each on a field which isn't a Vec, is an error.
+fn eachSetterName(field: &Field) -> syn::Result<Option<Ident>> {
+  let mut eachSetterName= None;
+
+  for attribute in &field.attrs {
+    if !attribute.path( ).is_ident("builder") {
+      continue }
+
+    let expectedEach= || {
+      syn::Error::new_spanned(&attribute.meta, "expected `builder(each = \"...\")`")};
+
+    let Meta::List(ref list)= attribute.meta else { return Err(expectedEach( )) };
+    let nameValue: MetaNameValue= list.parse_args( ).map_err(|_| expectedEach( ))?;
+
+    if !nameValue.path.is_ident("each") {
+      return Err(expectedEach( ))}
+
+    let Expr::Lit(ExprLit { lit: Lit::Str(ref name), .. })= nameValue.value else {
+      return Err(expectedEach( ))};
+
+    if unwrapVecType(&field.ty).is_none( ) {
+      return Err(syn::Error::new_spanned(&field.ty, "`each` can only be used on a `Vec` field"))}
+
+    eachSetterName= Some(name.parse::<Ident>( )?);
+  }
+
+  Ok(eachSetterName)
+}
+
+fn compileError<T: ToTokens>(tokens: T, message: &str) -> TokenStream {
+  syn::Error::new_spanned(tokens, message).to_compile_error( ).into( )
+}
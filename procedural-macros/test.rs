@@ -14,10 +14,15 @@ pub struct Command {
 }
 
 fn main( ) {
-  Command::builder( )
+  let command= Command::builder( )
     .executable("cargo".to_owned( ))
-    .args(vec!{ "build".to_owned( ), "--release".to_owned( ) })
-    .env(vec!{ })
+    .arg("build".to_owned( ))
+    .arg("--release".to_owned( ))
     .build( )
     .unwrap( );
+
+  assert_eq!(command.executable, "cargo");
+  assert_eq!(command.args, vec!{ "build".to_owned( ), "--release".to_owned( ) });
+  assert!(command.env.is_empty( ));
+  assert_eq!(command.current_dir, None);
 }
\ No newline at end of file
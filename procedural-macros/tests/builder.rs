@@ -0,0 +1,92 @@
+#![allow(non_snake_case)]
+
+use procedural_macros::Builder;
+
+#[derive(Builder)]
+pub struct Command {
+  executable: String,
+
+  #[builder(each = "arg")]
+  args: Vec<String>,
+
+  #[builder(each = "env")]
+  env: Vec<String>,
+
+  current_dir: Option<String>,
+}
+
+#[test]
+fn case_EachSetter( ) {
+  let command= Command::builder( )
+    .executable("cargo".to_owned( ))
+    .arg("build".to_owned( ))
+    .arg("--release".to_owned( ))
+    .build( )
+    .unwrap( );
+
+  assert_eq!(command.executable, "cargo");
+  assert_eq!(command.args, vec!{ "build", "--release" });
+
+  // Each fields default to being empty, instead of failing the build.
+  assert!(command.env.is_empty( ));
+  assert_eq!(command.current_dir, None);
+}
+
+#[test]
+fn case_AllAtOnceSetter( ) {
+  let command= Command::builder( )
+    .executable("cargo".to_owned( ))
+    .arg("build".to_owned( ))
+    .args(vec!{ "test".to_owned( ) })
+    .build( )
+    .unwrap( );
+
+  assert_eq!(command.args, vec!{ "test" });
+}
+
+#[test]
+fn case_MissingRequiredField( ) {
+  let error= Command::builder( ).arg("build".to_owned( )).build( ).err( ).unwrap( );
+  assert_eq!(error.to_string( ), "executable is not set");
+}
+
+#[derive(Builder)]
+pub struct Request<'a, T: Clone>
+  where T: Default
+{
+  url: &'a str,
+  body: T,
+
+  #[builder(each = "header")]
+  headers: Vec<&'a str>,
+
+  timeout: Option<u32>,
+}
+
+#[test]
+fn case_Generics( ) {
+  let url= String::from("https://example.com");
+
+  let request= Request::builder( )
+    .url(&url)
+    .body(vec!{ 1u8, 2 })
+    .header("Accept: */*")
+    .build( )
+    .unwrap( );
+
+  assert_eq!(request.url, "https://example.com");
+  assert_eq!(request.body, vec!{ 1, 2 });
+  assert_eq!(request.headers, vec!{ "Accept: */*" });
+  assert_eq!(request.timeout, None);
+}
+
+#[derive(Builder)]
+pub struct Packet<const N: usize, T: Clone = u8> {
+  bytes: [T; N],
+}
+
+#[test]
+fn case_ConstGenericsAndDefaults( ) {
+  let packet: Packet<3>= Packet::builder( ).bytes([ 1, 2, 3 ]).build( ).unwrap( );
+  assert_eq!(packet.bytes, [ 1, 2, 3 ]);
+}
@@ -0,0 +1,8 @@
+#![allow(non_snake_case)]
+
+#[test]
+fn ui( ) {
+  let testCases= trybuild::TestCases::new( );
+  testCases.pass("tests/ui/pass-*.rs");
+  testCases.compile_fail("tests/ui/fail-*.rs");
+}
@@ -0,0 +1,9 @@
+use procedural_macros::Builder;
+
+#[derive(Builder)]
+pub struct Command {
+  #[builder(each = "arg")]
+  executable: String,
+}
+
+fn main( ) { }
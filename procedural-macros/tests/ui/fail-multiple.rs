@@ -0,0 +1,13 @@
+use procedural_macros::Builder;
+
+// Every malformed attribute is reported, and not just the first one.
+#[derive(Builder)]
+pub struct Command {
+  #[builder(each = "arg")]
+  executable: String,
+
+  #[builder(eac = "env")]
+  env: Vec<String>,
+}
+
+fn main( ) { }
@@ -0,0 +1,11 @@
+use procedural_macros::Builder;
+
+#[derive(Builder)]
+pub struct Command {
+  executable: String,
+
+  #[builder(each = arg)]
+  args: Vec<String>,
+}
+
+fn main( ) { }
@@ -0,0 +1,11 @@
+use procedural_macros::Builder;
+
+#[derive(Builder)]
+pub struct Command {
+  executable: String,
+
+  #[builder(eac = "arg")]
+  args: Vec<String>,
+}
+
+fn main( ) { }
@@ -0,0 +1,27 @@
+use procedural_macros::Builder;
+
+#[derive(Builder)]
+pub struct Command {
+  executable: String,
+
+  #[builder(each = "arg")]
+  args: Vec<String>,
+
+  // The each setter's name collides with the field's, so there's no all at once setter.
+  #[builder(each = "env")]
+  env: Vec<String>,
+}
+
+fn main( ) {
+  let command= Command::builder( )
+    .executable("cargo".to_owned( ))
+    .args(vec!{ "build".to_owned( ) })
+    .arg("--release".to_owned( ))
+    .env("RUSTFLAGS=-Dwarnings".to_owned( ))
+    .build( )
+    .unwrap( );
+
+  assert_eq!(command.executable, "cargo");
+  assert_eq!(command.args, vec!{ "build", "--release" });
+  assert_eq!(command.env, vec!{ "RUSTFLAGS=-Dwarnings" });
+}
@@ -0,0 +1,29 @@
+use std::fmt::Debug;
+
+use procedural_macros::Builder;
+
+#[derive(Builder)]
+pub struct Request<'a, T: Clone, const N: usize, U = String>
+  where T: Default + Debug,
+        U: Clone
+{
+  url: &'a str,
+  body: T,
+  checksum: [u8; N],
+  metadata: Option<U>,
+
+  #[builder(each = "header")]
+  headers: Vec<&'a str>,
+}
+
+fn main( ) {
+  let request: Request<'_, Vec<u8>, 2>= Request::builder( )
+    .url("https://example.com")
+    .body(vec!{ 1, 2 })
+    .checksum([ 0xAB, 0xCD ])
+    .header("Accept: */*")
+    .build( )
+    .unwrap( );
+
+  assert_eq!(request.metadata, None);
+}